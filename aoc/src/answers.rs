@@ -0,0 +1,90 @@
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use anyhow::{format_err, Error};
+
+/// Where the known-good answers for our puzzle inputs live, relative to the
+/// workspace root (see `workspace_path`).
+pub const ANSWERS_PATH: &str = "answers.txt";
+
+/// Known-good answers, one `<day> <part> <answer>` per line.
+#[derive(Debug, Default)]
+pub struct Answers {
+    known: HashMap<(usize, usize), String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verification<'a> {
+    Correct,
+    Wrong { expected: &'a str },
+    Unknown,
+}
+
+impl Answers {
+    /// Loads the answers registry, treating a missing file as empty.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match std::fs::read_to_string(path) {
+            Ok(s) => s.parse(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format_err!("Couldn't read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        self.known.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn verify(&self, day: usize, part: usize, answer: &str) -> Verification<'_> {
+        match self.get(day, part) {
+            None => Verification::Unknown,
+            Some(expected) if expected == answer => Verification::Correct,
+            Some(expected) => Verification::Wrong { expected },
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut known = HashMap::new();
+        for (idx, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let [day, part, answer] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+                return Err(format_err!(
+                    "Line {}: expected <day> <part> <answer>",
+                    idx + 1
+                ));
+            };
+            known.insert((day.parse()?, part.parse()?), answer.to_owned());
+        }
+        Ok(Self { known })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_and_verify() {
+        let answers: Answers = "# day part answer\n1 1 142\n1 2 281\n\n7 1 6440\n"
+            .parse()
+            .expect("parses ok");
+        assert_eq!(Some("281"), answers.get(1, 2));
+        assert_eq!(None, answers.get(7, 2));
+
+        assert_eq!(Verification::Correct, answers.verify(1, 1, "142"));
+        assert_eq!(
+            Verification::Wrong { expected: "6440" },
+            answers.verify(7, 1, "6439")
+        );
+        assert_eq!(Verification::Unknown, answers.verify(9, 1, "114"));
+
+        assert!("1 1".parse::<Answers>().is_err());
+        assert!("one 1 5".parse::<Answers>().is_err());
+    }
+}
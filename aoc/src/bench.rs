@@ -0,0 +1,402 @@
+use std::{
+    fmt::Display,
+    io::Write,
+    process::Command,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{format_err, Error};
+
+use crate::{input_path, registry, workspace_path};
+
+/// Every bench run appends to this file, relative to the workspace root (see
+/// `workspace_path`).
+pub const HISTORY_PATH: &str = "bench_history.tsv";
+
+/// Only changes that are at least this unlikely to be noise get flagged.
+const SIGNIFICANCE: f64 = 0.01;
+
+/// The timings for one part from one bench run.
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    /// Unix time in milliseconds when the bench run started, shared by every
+    /// record of that run.
+    run: u64,
+    commit: String,
+    machine: String,
+    day: usize,
+    part: usize,
+    samples: Vec<Duration>,
+}
+
+impl Record {
+    fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        sorted[sorted.len() / 2]
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let samples: Vec<String> = self
+            .samples
+            .iter()
+            .map(|d| d.as_nanos().to_string())
+            .collect();
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.run,
+            self.commit,
+            self.machine,
+            self.day,
+            self.part,
+            samples.join(",")
+        )
+    }
+}
+
+impl FromStr for Record {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [run, commit, machine, day, part, samples] = s.split('\t').collect::<Vec<_>>()[..]
+        else {
+            return Err(format_err!("Expected 6 tab separated fields: {s}"));
+        };
+        let samples = samples
+            .split(',')
+            .map(|n| n.parse().map(Duration::from_nanos))
+            .collect::<Result<Vec<_>, _>>()?;
+        if samples.is_empty() {
+            return Err(format_err!("No samples: {s}"));
+        }
+        Ok(Self {
+            run: run.parse()?,
+            commit: commit.to_owned(),
+            machine: machine.to_owned(),
+            day: day.parse()?,
+            part: part.parse()?,
+            samples,
+        })
+    }
+}
+
+fn current_commit() -> String {
+    let root = workspace_path("");
+    let Ok(out) = Command::new("git")
+        .arg("-C")
+        .arg(&root)
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+    else {
+        return "unknown".to_owned();
+    };
+    let commit = String::from_utf8_lossy(&out.stdout).trim().to_owned();
+    let dirty = Command::new("git")
+        .arg("-C")
+        .arg(&root)
+        .args(["diff", "--quiet", "HEAD"])
+        .status()
+        .map(|s| !s.success())
+        .unwrap_or(false);
+    match (commit.is_empty(), dirty) {
+        (true, _) => "unknown".to_owned(),
+        (false, true) => format!("{commit}+dirty"),
+        (false, false) => commit,
+    }
+}
+
+fn machine() -> String {
+    std::fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .map(|s| s.trim().to_owned())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("unknown".to_owned())
+}
+
+fn load_history() -> Result<Vec<Record>, Error> {
+    let path = workspace_path(HISTORY_PATH);
+    let s = match std::fs::read_to_string(&path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format_err!("Couldn't read {}: {e}", path.display())),
+    };
+    s.lines().map(str::parse).collect()
+}
+
+/// Times every part of `days` (all days if empty) `samples` times and appends
+/// the results to the history file, unless none of them could be run.
+pub fn bench(samples: usize, days: &[usize]) -> Result<(), Error> {
+    let run = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    let (commit, machine) = (current_commit(), machine());
+    let mut records = Vec::new();
+    for day in registry::DAYS
+        .iter()
+        .filter(|d| days.is_empty() || days.contains(&d.day))
+    {
+        let path = input_path(day.day);
+        let Ok(input) = std::fs::read_to_string(&path) else {
+            eprintln!("Skipping day {}: couldn't read {}", day.day, path.display());
+            continue;
+        };
+        for part in 1..=day.parts.len() {
+            // The first run pays for cold caches, so it doesn't count.
+            let timings = (0..=samples)
+                .map(|_| day.run(part, &input).map(|a| a.elapsed))
+                .collect::<Result<Vec<_>, _>>();
+            match timings {
+                Ok(samples) => records.push(Record {
+                    run,
+                    commit: commit.clone(),
+                    machine: machine.clone(),
+                    day: day.day,
+                    part,
+                    samples: samples[1..].to_vec(),
+                }),
+                Err(e) => eprintln!("Skipping day {} part {part}: {e}", day.day),
+            }
+        }
+    }
+
+    if records.is_empty() {
+        return Err(format_err!(
+            "Nothing was measured, so no history was recorded"
+        ));
+    }
+    let mut history = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(workspace_path(HISTORY_PATH))?;
+    for record in records.iter() {
+        writeln!(history, "{record}")?;
+    }
+
+    println!("Bench run {run} of {commit} on {machine}:");
+    for record in records {
+        println!(
+            "Day {} part {}: median {:?} over {} samples",
+            record.day,
+            record.part,
+            record.median(),
+            record.samples.len()
+        );
+    }
+    Ok(())
+}
+
+/// Standard normal CDF, using the Abramowitz and Stegun 7.1.26 approximation
+/// of erf which is good to about 1e-7.
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// One sided Mann-Whitney U test: the probability of seeing `current` at
+/// least this much slower than `baseline` if they came from the same
+/// distribution. Uses the normal approximation, so wants a handful of
+/// samples on each side.
+fn slower_p_value(baseline: &[Duration], current: &[Duration]) -> f64 {
+    let mut all: Vec<(Duration, bool)> = baseline
+        .iter()
+        .map(|d| (*d, false))
+        .chain(current.iter().map(|d| (*d, true)))
+        .collect();
+    all.sort();
+    // Tied samples share the average of their ranks.
+    let mut current_rank_sum = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        let rank = (i + j + 1) as f64 / 2.0;
+        current_rank_sum += rank * all[i..j].iter().filter(|s| s.1).count() as f64;
+        i = j;
+    }
+    let (n1, n2) = (baseline.len() as f64, current.len() as f64);
+    let u = current_rank_sum - n2 * (n2 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let sd = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+    if sd == 0.0 {
+        return 1.0;
+    }
+    1.0 - normal_cdf((u - mean) / sd)
+}
+
+#[derive(Debug, PartialEq)]
+struct Comparison {
+    day: usize,
+    part: usize,
+    baseline: Duration,
+    current: Duration,
+    /// Change in the median, in percent of the baseline.
+    change: f64,
+    p_value: f64,
+}
+
+impl Comparison {
+    fn regressed(&self, threshold: f64) -> bool {
+        self.p_value < SIGNIFICANCE && self.change > threshold
+    }
+}
+
+/// Picks the latest run on this machine and the baseline to compare it to:
+/// the latest run of `baseline` if given, otherwise the latest run of a
+/// different commit, falling back to the run before the latest one.
+fn compare_runs(
+    history: &[Record],
+    machine: &str,
+    baseline: Option<&str>,
+) -> Result<Vec<Comparison>, Error> {
+    let history: Vec<&Record> = history.iter().filter(|r| r.machine == machine).collect();
+    let current = history
+        .iter()
+        .max_by_key(|r| r.run)
+        .ok_or(format_err!("No bench runs recorded on {machine}"))?;
+    let (current_run, current_commit) = (current.run, current.commit.as_str());
+    let earlier = history.iter().filter(|r| r.run < current_run);
+    let base = match baseline {
+        Some(commit) => earlier
+            .filter(|r| r.commit.starts_with(commit))
+            .max_by_key(|r| r.run),
+        None => earlier
+            .clone()
+            .filter(|r| r.commit != current_commit)
+            .max_by_key(|r| r.run)
+            .or_else(|| earlier.max_by_key(|r| r.run)),
+    }
+    .ok_or(format_err!("No baseline run to compare against"))?;
+    let base_run = base.run;
+
+    let mut comparisons = Vec::new();
+    for cur in history.iter().filter(|r| r.run == current_run) {
+        let Some(base) = history
+            .iter()
+            .find(|r| r.run == base_run && r.day == cur.day && r.part == cur.part)
+        else {
+            continue;
+        };
+        let (baseline, current) = (base.median(), cur.median());
+        comparisons.push(Comparison {
+            day: cur.day,
+            part: cur.part,
+            baseline,
+            current,
+            change: (current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0,
+            p_value: slower_p_value(&base.samples, &cur.samples),
+        });
+    }
+    Ok(comparisons)
+}
+
+/// Compares the latest bench run against a baseline, failing if any part got
+/// significantly slower by more than `threshold` percent.
+pub fn compare(baseline: Option<&str>, threshold: f64) -> Result<(), Error> {
+    let comparisons = compare_runs(&load_history()?, &machine(), baseline)?;
+    let mut regressions = 0;
+    for c in comparisons.iter() {
+        let flag = if c.regressed(threshold) {
+            regressions += 1;
+            "  REGRESSION"
+        } else {
+            ""
+        };
+        println!(
+            "Day {} part {}: {:?} -> {:?} ({:+.1}%, p={:.4}){flag}",
+            c.day, c.part, c.baseline, c.current, c.change, c.p_value
+        );
+    }
+    if regressions > 0 {
+        return Err(format_err!(
+            "{regressions} parts regressed by more than {threshold}%"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn record(run: u64, commit: &str, day: usize, samples: &[u64]) -> Record {
+        Record {
+            run,
+            commit: commit.to_owned(),
+            machine: "box".to_owned(),
+            day,
+            part: 1,
+            samples: samples.iter().copied().map(Duration::from_micros).collect(),
+        }
+    }
+
+    #[test]
+    fn record_roundtrip() {
+        let r = record(1700000000, "abc1234", 7, &[10, 12, 11]);
+        assert_eq!(
+            "1700000000\tabc1234\tbox\t7\t1\t10000,12000,11000",
+            r.to_string()
+        );
+        assert_eq!(r, r.to_string().parse().unwrap());
+        assert_eq!(Duration::from_micros(11), r.median());
+
+        assert!("1\tabc\tbox\t7\t1".parse::<Record>().is_err());
+        assert!("1\tabc\tbox\t7\t1\t".parse::<Record>().is_err());
+    }
+
+    #[test]
+    fn significance() {
+        let base: Vec<Duration> = (100..110).map(Duration::from_micros).collect();
+        let doubled: Vec<Duration> = (200..210).map(Duration::from_micros).collect();
+        assert!(slower_p_value(&base, &doubled) < 0.001);
+        assert!(slower_p_value(&doubled, &base) > 0.999);
+        assert!(slower_p_value(&base, &base) > 0.4);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+    }
+
+    #[test]
+    fn compares_against_previous_commit() {
+        let base = [100, 101, 102, 103, 104, 105, 106, 107];
+        let slow = [200, 201, 202, 203, 204, 205, 206, 207];
+        let history = vec![
+            record(1, "aaa", 7, &slow),
+            record(2, "bbb", 7, &base),
+            record(2, "bbb", 8, &base),
+            record(3, "ccc", 7, &slow),
+            record(3, "ccc", 8, &base),
+        ];
+
+        let comparisons = compare_runs(&history, "box", None).unwrap();
+        assert_eq!(2, comparisons.len());
+        assert!(comparisons[0].regressed(50.0));
+        assert!(!comparisons[0].regressed(150.0));
+        assert!(!comparisons[1].regressed(10.0));
+
+        let comparisons = compare_runs(&history, "box", Some("aaa")).unwrap();
+        assert_eq!(1, comparisons.len());
+        assert!(!comparisons[0].regressed(10.0));
+
+        // With a single commit in the history, compare to the run before.
+        let history = vec![record(1, "aaa", 7, &base), record(2, "aaa", 7, &slow)];
+        let comparisons = compare_runs(&history, "box", None).unwrap();
+        assert!(comparisons[0].regressed(10.0));
+
+        assert!(compare_runs(&history, "other", None).is_err());
+        assert!(compare_runs(&history[..1], "box", None).is_err());
+    }
+}
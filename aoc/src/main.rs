@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use anyhow::{format_err, Error};
+
+mod answers;
+mod bench;
+mod profile;
+mod registry;
+mod server;
+mod tui;
+
+const USAGE: &str = "usage: aoc run <day> [<part>] [<input>]
+       aoc serve [<addr>]
+       aoc tui
+       aoc bench [-n <samples>] [<day>...]
+       aoc bench compare [<baseline-commit>] [-t <threshold-percent>]
+       aoc profile [<day>...]";
+
+/// `path` under the workspace root, wherever aoc is run from.
+fn workspace_path(path: &str) -> PathBuf {
+    PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/..")).join(path)
+}
+
+/// Where a day's puzzle input is kept.
+fn input_path(day: usize) -> PathBuf {
+    workspace_path(&format!("day-{day}/input.txt"))
+}
+
+fn main() -> Result<(), Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["run", day, rest @ ..] => {
+            let day = registry::find(day.parse()?).ok_or(format_err!("No solver for day {day}"))?;
+            let (parts, path) = match rest {
+                [] => (vec![1, 2], None),
+                [part] if part.parse::<usize>().is_ok() => (vec![part.parse()?], None),
+                [path] => (vec![1, 2], Some(*path)),
+                [part, path] => (vec![part.parse()?], Some(*path)),
+                _ => return Err(format_err!(USAGE)),
+            };
+            let path = path
+                .map(PathBuf::from)
+                .unwrap_or_else(|| input_path(day.day));
+            let input = std::fs::read_to_string(&path)
+                .map_err(|e| format_err!("Couldn't read {}: {e}", path.display()))?;
+            for part in parts {
+                if !(1..=day.parts.len()).contains(&part) {
+                    return Err(format_err!("No part {part} for day {}", day.day));
+                }
+                let answer = day.run(part, &input)?;
+                println!(
+                    "Day {} part {part} result: {} in {:?}",
+                    day.day, answer.answer, answer.elapsed
+                );
+            }
+            Ok(())
+        }
+        ["serve", rest @ ..] => {
+            let addr = rest.first().copied().unwrap_or("127.0.0.1:8023");
+            let server = tiny_http::Server::http(addr).map_err(|e| format_err!("{e}"))?;
+            println!("Serving solvers on http://{addr}");
+            server::serve(&server)
+        }
+        ["tui"] => tui::tui(),
+        ["bench", "compare", rest @ ..] => {
+            let mut baseline = None;
+            let mut threshold = 10.0;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                match *arg {
+                    "-t" => threshold = rest.next().ok_or(format_err!(USAGE))?.parse()?,
+                    commit => baseline = Some(commit),
+                }
+            }
+            bench::compare(baseline, threshold)
+        }
+        ["bench", rest @ ..] => {
+            let mut samples = 10;
+            let mut days = Vec::new();
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                match *arg {
+                    "-n" => samples = rest.next().ok_or(format_err!(USAGE))?.parse()?,
+                    day => days.push(day.parse()?),
+                }
+            }
+            bench::bench(samples.max(1), &days)
+        }
+        ["profile", days @ ..] => {
+            let days = days
+                .iter()
+                .map(|d| d.parse())
+                .collect::<Result<Vec<usize>, _>>()?;
+            profile::profile(&days)
+        }
+        _ => Err(format_err!(USAGE)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_workspace() {
+        let manifest = std::fs::read_to_string(workspace_path("Cargo.toml")).unwrap();
+        assert!(manifest.starts_with("[workspace]"));
+        assert!(input_path(1).ends_with("day-1/input.txt"));
+        assert!(input_path(1).parent().unwrap().join("Cargo.toml").exists());
+    }
+}
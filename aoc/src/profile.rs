@@ -0,0 +1,204 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Display,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use anyhow::Error;
+
+use crate::{
+    input_path,
+    registry::{self, guarded, SolveError},
+};
+
+/// Wraps the system allocator, counting every allocation so the profile mode
+/// can report what each solver step costs. Counting starts with the first
+/// `measure`, so the other commands only pay for checking `COUNTING`.
+struct CountingAlloc;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+impl CountingAlloc {
+    fn grew(size: usize) {
+        if !COUNTING.load(Ordering::Relaxed) {
+            return;
+        }
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrank(size: usize) {
+        if !COUNTING.load(Ordering::Relaxed) {
+            return;
+        }
+        // Blocks from before counting started weren't added, so don't let
+        // freeing them wrap around.
+        let _ = CURRENT.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_sub(size))
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrank(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            // Count a realloc as freeing the old block and allocating a new one.
+            Self::shrank(layout.size());
+            Self::grew(new_size);
+        }
+        new
+    }
+}
+
+/// What one step cost. `peak` is the most memory held at once during the
+/// step, over what was already held when it started.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Usage {
+    elapsed: Duration,
+    allocations: usize,
+    allocated: usize,
+    peak: usize,
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    COUNTING.store(true, Ordering::Relaxed);
+    let (allocations, allocated) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED.load(Ordering::Relaxed),
+    );
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    let start = Instant::now();
+    let res = f();
+    let usage = Usage {
+        elapsed: start.elapsed(),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(current),
+    };
+    (res, usage)
+}
+
+struct Bytes(usize);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            b if b < 1 << 10 => write!(f, "{b}B"),
+            b if b < 1 << 20 => write!(f, "{:.1}KiB", b as f64 / (1 << 10) as f64),
+            b => write!(f, "{:.1}MiB", b as f64 / (1 << 20) as f64),
+        }
+    }
+}
+
+impl Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}, {} allocations, {} allocated, {} peak",
+            self.elapsed,
+            self.allocations,
+            Bytes(self.allocated),
+            Bytes(self.peak)
+        )
+    }
+}
+
+/// Runs the parse and each part of `days` (all days if empty) once, reporting
+/// time and allocations for each step.
+pub fn profile(days: &[usize]) -> Result<(), Error> {
+    for day in registry::DAYS
+        .iter()
+        .filter(|d| days.is_empty() || days.contains(&d.day))
+    {
+        let path = input_path(day.day);
+        let Ok(input) = std::fs::read_to_string(&path) else {
+            eprintln!("Skipping day {}: couldn't read {}", day.day, path.display());
+            continue;
+        };
+        let (parsed, usage) = measure(|| day.parse_input(&input));
+        println!("Day {} parse: {usage}", day.day);
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                println!("Day {}: {e}", day.day);
+                continue;
+            }
+        };
+        for (idx, part) in day.parts.iter().enumerate() {
+            let (answer, usage): (Result<String, SolveError>, _) =
+                measure(|| guarded(|| part(&parsed)));
+            match answer {
+                Ok(answer) => println!(
+                    "Day {} part {} result: {answer} in {usage}",
+                    day.day,
+                    idx + 1
+                ),
+                Err(e) => println!("Day {} part {}: {e}", day.day, idx + 1),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn counts_allocations() {
+        let (v, usage) = measure(|| {
+            let v: Vec<u64> = Vec::with_capacity(1000);
+            let w: Vec<u64> = Vec::with_capacity(1000);
+            drop(w);
+            v
+        });
+        assert_eq!(1000, v.capacity());
+        // Other tests may allocate at the same time, so only check the floor.
+        assert!(usage.allocations >= 2);
+        assert!(usage.allocated >= 16000);
+        assert!(usage.peak >= 16000);
+    }
+
+    #[test]
+    fn byte_sizes() {
+        assert_eq!("512B", Bytes(512).to_string());
+        assert_eq!("1.5KiB", Bytes(1536).to_string());
+        assert_eq!("2.0MiB", Bytes(2 << 20).to_string());
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    any::Any,
+    fmt::Display,
+    panic::{catch_unwind, AssertUnwindSafe},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{format_err, Error};
+use thiserror::Error;
+
+/// Whatever a day's parse step produces, handed to each of its parts.
+pub type Parsed = Box<dyn Any>;
+
+pub type Parse = fn(&str) -> Result<Parsed, SolveError>;
+
+/// A part solver takes the parsed puzzle and produces the answer as text.
+pub type Part = fn(&Parsed) -> Result<String, SolveError>;
+
+pub struct Day {
+    pub day: usize,
+    pub parse: Parse,
+    pub parts: [Part; 2],
+}
+
+#[derive(Error, Debug)]
+pub enum SolveError {
+    #[error("Parse error: {0}")]
+    Parse(Error),
+    #[error("Solver error: {0}")]
+    Solve(Error),
+    #[error("Solver panicked: {0}")]
+    Panic(String),
+}
+
+#[derive(Debug)]
+pub struct Answer {
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+// Day 1 reads its lines inside its part functions, since part 2 reads them
+// differently to part 1, so its parse step only copies the text and any error
+// its parts return is a problem with the input.
+fn text(input: &str) -> Result<Parsed, SolveError> {
+    Ok(Box::new(input.to_owned()))
+}
+
+fn on_text<T: Display>(
+    parsed: &Parsed,
+    part: fn(&str) -> Result<T, Error>,
+) -> Result<String, SolveError> {
+    let input = parsed.downcast_ref::<String>().unwrap();
+    part(input)
+        .map(|a| a.to_string())
+        .map_err(SolveError::Parse)
+}
+
+fn parse<P: FromStr<Err = Error> + 'static>(input: &str) -> Result<Parsed, SolveError> {
+    match input.parse::<P>() {
+        Ok(parsed) => Ok(Box::new(parsed)),
+        Err(e) => Err(SolveError::Parse(e)),
+    }
+}
+
+fn on_parsed<P: 'static, T: Display>(
+    parsed: &Parsed,
+    part: impl FnOnce(&P) -> Result<T, Error>,
+) -> Result<String, SolveError> {
+    part(parsed.downcast_ref::<P>().unwrap())
+        .map(|a| a.to_string())
+        .map_err(SolveError::Solve)
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        parse: text,
+        parts: [
+            |p| on_text(p, day_1::part1),
+            |p| on_text(p, |s| day_1::part2(s, &day_1::Vocabulary::english())),
+        ],
+    },
+    Day {
+        day: 2,
+        parse: parse::<day_2::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, |g| day_2::part1(g, &day_2::Bag::default())),
+            |p| on_parsed(p, |g| day_2::part2(g, &day_2::Bag::default())),
+        ],
+    },
+    Day {
+        day: 3,
+        parse: parse::<day_3::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_3::part1),
+            |p| on_parsed(p, day_3::part2),
+        ],
+    },
+    Day {
+        day: 4,
+        parse: parse::<day_4::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_4::part1),
+            |p| on_parsed(p, day_4::part2),
+        ],
+    },
+    Day {
+        day: 5,
+        parse: parse::<day_5::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_5::part1),
+            |p| on_parsed(p, day_5::part2),
+        ],
+    },
+    Day {
+        day: 6,
+        parse: parse::<day_6::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_6::part1),
+            |p| on_parsed(p, |p| day_6::part2(&day_6::keming_input(p))),
+        ],
+    },
+    Day {
+        day: 7,
+        parse: parse::<day_7::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_7::part1),
+            |p| on_parsed(p, day_7::part2),
+        ],
+    },
+    Day {
+        day: 8,
+        parse: parse::<day_8::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_8::part1),
+            |p| on_parsed(p, day_8::part2),
+        ],
+    },
+    Day {
+        day: 9,
+        parse: parse::<day_9::ParsedInput>,
+        parts: [
+            |p| on_parsed(p, day_9::part1),
+            |p| on_parsed(p, day_9::part2),
+        ],
+    },
+];
+
+pub fn find(day: usize) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/// Runs `f`, turning any panic in it into a `SolveError`.
+pub fn guarded<T>(f: impl FnOnce() -> Result<T, SolveError>) -> Result<T, SolveError> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(res) => res,
+        Err(panic) => Err(SolveError::Panic(
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default(),
+        )),
+    }
+}
+
+impl Day {
+    /// Parses `input`, counting a panic in the parser as a parse error since
+    /// it was the input that set it off.
+    pub fn parse_input(&self, input: &str) -> Result<Parsed, SolveError> {
+        guarded(|| (self.parse)(input)).map_err(|e| match e {
+            SolveError::Panic(msg) => SolveError::Parse(format_err!("Parser panicked: {msg}")),
+            e => e,
+        })
+    }
+
+    /// Parses `input` and runs one part (1 or 2) on it, timing both together.
+    pub fn run(&self, part: usize, input: &str) -> Result<Answer, SolveError> {
+        let start = Instant::now();
+        let parsed = self.parse_input(input)?;
+        let answer = guarded(|| self.parts[part - 1](&parsed))?;
+        Ok(Answer {
+            answer,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn runs_examples() {
+        let answer = find(7)
+            .unwrap()
+            .run(1, "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483")
+            .unwrap();
+        assert_eq!("6440", answer.answer);
+
+        let answer = find(1).unwrap().run(1, "1abc2\ntreb7uchet").unwrap();
+        assert_eq!("89", answer.answer);
+    }
+
+    #[test]
+    fn errors() {
+        let res = find(1).unwrap().run(1, "abc");
+        assert!(matches!(res, Err(SolveError::Parse(_))));
+
+        let res = find(9).unwrap().run(2, "1 2 x");
+        assert!(matches!(res, Err(SolveError::Parse(_))));
+
+        let res = find(7).unwrap().run(1, "ZZZZZ 1");
+        assert!(matches!(res, Err(SolveError::Parse(_))));
+
+        // BBB is parsed fine but has no node of its own to follow.
+        let res = find(8).unwrap().run(1, "L\n\nAAA = (BBB, BBB)");
+        assert!(matches!(res, Err(SolveError::Panic(_))));
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::Error;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::registry::{self, SolveError};
+
+/// Answers requests on `server` until it is unblocked.
+pub fn serve(server: &Server) -> Result<(), Error> {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, json) = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => route(request.method(), request.url(), &body),
+            Err(e) => (400, json!({ "error": format!("Couldn't read body: {e}") })),
+        };
+        println!("{} {} -> {status}", request.method(), request.url());
+        let response = Response::from_string(json.to_string())
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+        // A client hanging up before its answer is sent is its own problem,
+        // not a reason to stop serving everyone else.
+        if let Err(e) = request.respond(response) {
+            eprintln!("Couldn't send response: {e}");
+        }
+    }
+    Ok(())
+}
+
+fn route(method: &Method, url: &str, body: &str) -> (u16, Value) {
+    let segments: Vec<&str> = url.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["days"]) => (200, days()),
+        (Method::Post, ["day", day, "part", part]) => {
+            let (Ok(day), Ok(part)) = (day.parse::<usize>(), part.parse::<usize>()) else {
+                return (404, json!({ "error": format!("No solver at {url}") }));
+            };
+            solve(day, part, body)
+        }
+        (_, ["days"] | ["day", _, "part", _]) => (
+            405,
+            json!({ "error": format!("{method} not allowed on {url}") }),
+        ),
+        _ => (404, json!({ "error": format!("No route for {url}") })),
+    }
+}
+
+fn days() -> Value {
+    registry::DAYS
+        .iter()
+        .map(|d| json!({ "day": d.day, "parts": (1..=d.parts.len()).collect::<Vec<_>>() }))
+        .collect()
+}
+
+fn solve(day: usize, part: usize, input: &str) -> (u16, Value) {
+    let Some(solver) = registry::find(day).filter(|d| (1..=d.parts.len()).contains(&part)) else {
+        return (
+            404,
+            json!({ "error": format!("No solver for day {day} part {part}") }),
+        );
+    };
+    match solver.run(part, input) {
+        Ok(answer) => (
+            200,
+            json!({
+                "day": day,
+                "part": part,
+                "answer": answer.answer,
+                "elapsed_us": answer.elapsed.as_micros() as u64,
+            }),
+        ),
+        Err(e) => {
+            let status = match e {
+                SolveError::Parse(_) => 400,
+                SolveError::Solve(_) => 422,
+                SolveError::Panic(_) => 500,
+            };
+            (status, json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::Arc,
+        thread,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn routes() {
+        let (status, json) = route(&Method::Get, "/days", "");
+        assert_eq!(200, status);
+        assert_eq!(9, json.as_array().unwrap().len());
+        assert_eq!(json!({ "day": 1, "parts": [1, 2] }), json[0]);
+
+        let (status, json) = route(&Method::Post, "/day/1/part/1", "1abc2\npqr3stu8vwx");
+        assert_eq!(200, status);
+        assert_eq!("50", json["answer"]);
+
+        let (status, json) = route(&Method::Post, "/day/1/part/1", "abc");
+        assert_eq!(400, status);
+        assert_eq!("Parse error: doesn't contain a number", json["error"]);
+
+        let (status, json) = route(&Method::Post, "/day/7/part/1", "ZZZZZ 1");
+        assert_eq!(400, status);
+        assert_eq!("Parse error: Not a card: Z", json["error"]);
+
+        assert_eq!(404, route(&Method::Post, "/day/10/part/1", "").0);
+        assert_eq!(404, route(&Method::Post, "/day/1/part/3", "").0);
+        assert_eq!(404, route(&Method::Post, "/day/one/part/1", "").0);
+        assert_eq!(405, route(&Method::Get, "/day/1/part/1", "").0);
+        assert_eq!(404, route(&Method::Get, "/", "").0);
+    }
+
+    #[test]
+    fn over_localhost() {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let addr = server.server_addr().to_ip().unwrap();
+        let handle = {
+            let server = server.clone();
+            thread::spawn(move || serve(&server))
+        };
+
+        // A client that hangs up without reading its answer.
+        let mut quitter = TcpStream::connect(addr).unwrap();
+        write!(quitter, "GET /days HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        drop(quitter);
+
+        let body = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST /day/9/part/1 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, json) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200"));
+        let json: Value = serde_json::from_str(json).unwrap();
+        assert_eq!("114", json["answer"]);
+        assert!(json["elapsed_us"].is_u64());
+
+        server.unblock();
+        handle.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    collections::HashMap,
+    process::Command,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Error;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Row, Table, TableState},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    answers::{Answers, Verification, ANSWERS_PATH},
+    registry::{self, Day},
+    workspace_path,
+};
+
+/// Results of the most recent runs, kept between sessions, relative to the
+/// workspace root.
+const LAST_RUNS_PATH: &str = "target/aoc-last-runs.txt";
+
+#[derive(Debug, Clone, PartialEq)]
+struct LastRun {
+    answer: String,
+    elapsed: String,
+}
+
+/// Reads `<day>\t<part>\t<answer>\t<elapsed>` lines, skipping anything that
+/// doesn't fit.
+fn parse_last_runs(s: &str) -> HashMap<(usize, usize), LastRun> {
+    s.lines()
+        .filter_map(|line| {
+            let [day, part, answer, elapsed] = line.split('\t').collect::<Vec<_>>()[..] else {
+                return None;
+            };
+            let run = LastRun {
+                answer: answer.to_owned(),
+                elapsed: elapsed.to_owned(),
+            };
+            Some(((day.parse().ok()?, part.parse().ok()?), run))
+        })
+        .collect()
+}
+
+fn save_last_runs(runs: &HashMap<(usize, usize), LastRun>) -> Result<(), Error> {
+    let mut keys: Vec<_> = runs.keys().collect();
+    keys.sort();
+    let s: String = keys
+        .into_iter()
+        .map(|k| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                k.0, k.1, runs[k].answer, runs[k].elapsed
+            )
+        })
+        .collect();
+    let path = workspace_path(LAST_RUNS_PATH);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    Ok(std::fs::write(path, s)?)
+}
+
+/// Picks the answer out of the output of `aoc run <day> <part>`.
+fn parse_result_line(stdout: &str, day: usize, part: usize) -> Option<LastRun> {
+    let prefix = format!("Day {day} part {part} result: ");
+    let (answer, elapsed) = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix(&prefix))?
+        .rsplit_once(" in ")?;
+    Some(LastRun {
+        answer: answer.to_owned(),
+        elapsed: elapsed.to_owned(),
+    })
+}
+
+enum Message {
+    Part {
+        day: usize,
+        part: usize,
+        result: Result<LastRun, String>,
+        log: String,
+    },
+    Done,
+}
+
+/// Runs one part through `aoc run` in a child process so the solver's own
+/// printing ends up in the log pane instead of on top of the UI.
+fn run_part(day: usize, part: usize) -> Message {
+    let output = std::env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(["run", &day.to_string(), &part.to_string()])
+            .output()
+    });
+    let (result, log) = match output {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let result = parse_result_line(&stdout, day, part).ok_or_else(|| {
+                stderr
+                    .lines()
+                    .find(|l| l.starts_with("Error"))
+                    .unwrap_or("no result printed")
+                    .to_owned()
+            });
+            (result, format!("{stdout}{stderr}"))
+        }
+        Err(e) => (Err(format!("Couldn't start solver: {e}")), String::new()),
+    };
+    Message::Part {
+        day,
+        part,
+        result,
+        log,
+    }
+}
+
+struct App {
+    answers: Answers,
+    last_runs: HashMap<(usize, usize), LastRun>,
+    errors: HashMap<(usize, usize), String>,
+    logs: HashMap<usize, String>,
+    table: TableState,
+    running: Option<usize>,
+    tx: Sender<Message>,
+    rx: Receiver<Message>,
+}
+
+pub fn tui() -> Result<(), Error> {
+    let answers = Answers::load(&workspace_path(ANSWERS_PATH))?;
+    let last_runs = std::fs::read_to_string(workspace_path(LAST_RUNS_PATH))
+        .map(|s| parse_last_runs(&s))
+        .unwrap_or_default();
+    let mut app = App::new(answers, last_runs);
+    ratatui::run(|terminal| app.run(terminal))?;
+    save_last_runs(&app.last_runs)
+}
+
+impl App {
+    fn new(answers: Answers, last_runs: HashMap<(usize, usize), LastRun>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        App {
+            answers,
+            last_runs,
+            errors: HashMap::new(),
+            logs: HashMap::new(),
+            table: TableState::default().with_selected(0),
+            running: None,
+            tx,
+            rx,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            while let Ok(message) = self.rx.try_recv() {
+                self.receive(message);
+            }
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::Char('r') | KeyCode::Enter => self.start(vec![1, 2]),
+                KeyCode::Char('1') => self.start(vec![1]),
+                KeyCode::Char('2') => self.start(vec![2]),
+                _ => {}
+            }
+        }
+    }
+
+    fn selected(&self) -> &'static Day {
+        let idx = self.table.selected().unwrap_or(0);
+        &registry::DAYS[idx.min(registry::DAYS.len() - 1)]
+    }
+
+    fn start(&mut self, parts: Vec<usize>) {
+        if self.running.is_some() {
+            return;
+        }
+        let day = self.selected().day;
+        self.running = Some(day);
+        self.logs.remove(&day);
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            for part in parts {
+                let _ = tx.send(run_part(day, part));
+            }
+            let _ = tx.send(Message::Done);
+        });
+    }
+
+    fn receive(&mut self, message: Message) {
+        match message {
+            Message::Part {
+                day,
+                part,
+                result,
+                log,
+            } => {
+                self.logs.entry(day).or_default().push_str(&log);
+                match result {
+                    Ok(run) => {
+                        self.errors.remove(&(day, part));
+                        self.last_runs.insert((day, part), run);
+                    }
+                    Err(e) => {
+                        self.errors.insert((day, part), e);
+                    }
+                }
+            }
+            Message::Done => self.running = None,
+        }
+    }
+
+    fn status(&self, day: usize, part: usize) -> (String, &'static str, Color) {
+        if self.errors.contains_key(&(day, part)) {
+            return ("error".to_owned(), "!", Color::Red);
+        }
+        let Some(run) = self.last_runs.get(&(day, part)) else {
+            return ("-".to_owned(), "", Color::DarkGray);
+        };
+        let (mark, color) = match self.answers.verify(day, part, &run.answer) {
+            Verification::Correct => ("ok", Color::Green),
+            Verification::Wrong { .. } => ("WRONG", Color::Red),
+            Verification::Unknown => ("?", Color::Yellow),
+        };
+        (run.answer.clone(), mark, color)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [list, side] =
+            Layout::horizontal([Constraint::Length(62), Constraint::Fill(1)]).areas(main);
+
+        let rows = registry::DAYS.iter().map(|d| {
+            let mut cells = vec![Line::from(format!("Day {}", d.day))];
+            for part in 1..=d.parts.len() {
+                let (answer, mark, color) = self.status(d.day, part);
+                let elapsed = self
+                    .last_runs
+                    .get(&(d.day, part))
+                    .map(|r| r.elapsed.clone())
+                    .unwrap_or_default();
+                cells.push(Line::from(answer));
+                cells.push(Line::from(mark).fg(color));
+                cells.push(Line::from(elapsed));
+            }
+            let row = Row::new(cells);
+            if self.running == Some(d.day) {
+                row.yellow()
+            } else {
+                row
+            }
+        });
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Fill(1),
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Fill(1),
+            Constraint::Length(5),
+            Constraint::Length(10),
+        ];
+        let header = Row::new(["", "Part 1", "", "Time", "Part 2", "", "Time"]).bold();
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title("Days"))
+            .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, list, &mut self.table);
+
+        let day = self.selected().day;
+        let mut text = Text::default();
+        for part in 1..=self.selected().parts.len() {
+            if let Some(e) = self.errors.get(&(day, part)) {
+                text.push_line(Line::from(format!("Part {part}: {e}")).red());
+                continue;
+            }
+            let Some(run) = self.last_runs.get(&(day, part)) else {
+                text.push_line(format!("Part {part}: not run yet"));
+                continue;
+            };
+            let line = format!("Part {part}: {} in {}", run.answer, run.elapsed);
+            text.push_line(match self.answers.verify(day, part, &run.answer) {
+                Verification::Correct => Line::from(format!("{line}, correct")).green(),
+                Verification::Wrong { expected } => {
+                    Line::from(format!("{line}, expected {expected}")).red()
+                }
+                Verification::Unknown => Line::from(format!("{line}, no known answer")),
+            });
+        }
+        text.push_line("");
+        match (self.running, self.logs.get(&day)) {
+            (Some(running), _) if running == day => text.push_line("running…".yellow()),
+            (_, Some(log)) => log.lines().for_each(|l| text.push_line(l.to_owned())),
+            (_, None) => {}
+        }
+        let height = side.height.saturating_sub(2) as usize;
+        let scroll = text.lines.len().saturating_sub(height) as u16;
+        let side_pane = Paragraph::new(text)
+            .block(Block::bordered().title(format!("Day {day}")))
+            .scroll((scroll, 0));
+        frame.render_widget(side_pane, side);
+
+        frame.render_widget(
+            Line::from("↑/↓ select  r run day  1/2 run part  q quit").dark_gray(),
+            help,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn result_lines() {
+        let stdout = "found seed to soil mapping\nDay 5 part 1 result: 35 in 1.2ms\n";
+        assert_eq!(
+            Some(LastRun {
+                answer: "35".to_owned(),
+                elapsed: "1.2ms".to_owned()
+            }),
+            parse_result_line(stdout, 5, 1)
+        );
+        assert_eq!(None, parse_result_line(stdout, 5, 2));
+    }
+
+    #[test]
+    fn draws_verification() {
+        let answers = "1 1 142\n1 2 280\n".parse().unwrap();
+        let last_runs = parse_last_runs("1\t1\t142\t10µs\n1\t2\t281\t12µs\n");
+        let mut app = App::new(answers, last_runs);
+        let mut terminal = Terminal::new(TestBackend::new(120, 14)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(screen.contains("WRONG"));
+        assert!(screen.contains("Part 1: 142 in 10µs, correct"));
+        assert!(screen.contains("Part 2: 281 in 12µs, expected 280"));
+    }
+
+    #[test]
+    fn last_runs() {
+        let runs = parse_last_runs("1\t1\t142\t10µs\n1\t2\t281\t12µs\nnonsense\n");
+        assert_eq!(2, runs.len());
+        assert_eq!("281", runs[&(1, 2)].answer);
+    }
+}
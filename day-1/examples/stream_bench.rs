@@ -0,0 +1,101 @@
+//! Times the streaming and parallel part 1 over a generated input.
+//!
+//!     cargo run --release -p day-1 --example stream_bench -- [<GiB>] [<path>]
+//!
+//! The input is written to `<path>` (`target/day-1-bench.txt` by default)
+//! unless a file of the right size is already there.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    time::Instant,
+};
+
+use anyhow::Error;
+
+use day_1::{part1_parallel, part1_reader};
+
+/// xorshift64, so the input is the same on every run without pulling in a
+/// random number crate.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Writes lines of letters with a few digits mixed in until there are `len`
+/// bytes, returning the expected sum.
+fn generate(path: &str, len: u64) -> Result<u64, Error> {
+    let mut out = BufWriter::with_capacity(1 << 20, File::create(path)?);
+    let mut rng = Rng(0x2023_1201);
+    let (mut written, mut sum) = (0, 0);
+    let mut line = Vec::new();
+    while written < len {
+        line.clear();
+        let width = 8 + rng.below(56) as usize;
+        line.extend((0..width).map(|_| b'a' + rng.below(26) as u8));
+        for _ in 0..1 + rng.below(3) {
+            let at = rng.below(width as u64) as usize;
+            line[at] = b'0' + rng.below(10) as u8;
+        }
+        let first = line.iter().find(|b| b.is_ascii_digit()).unwrap() - b'0';
+        let last = line.iter().rfind(|b| b.is_ascii_digit()).unwrap() - b'0';
+        sum += first as u64 * 10 + last as u64;
+        line.push(b'\n');
+        out.write_all(&line)?;
+        written += line.len() as u64;
+    }
+    out.flush()?;
+    Ok(sum)
+}
+
+fn report(name: &str, len: u64, f: impl FnOnce() -> Result<u64, Error>) -> Result<u64, Error> {
+    let start = Instant::now();
+    let sum = f()?;
+    let elapsed = start.elapsed();
+    let rate = len as f64 / (1 << 30) as f64 / elapsed.as_secs_f64();
+    println!("{name:>12}: {sum} in {elapsed:?} ({rate:.2} GiB/s)");
+    Ok(sum)
+}
+
+fn main() -> Result<(), Error> {
+    let mut args = std::env::args().skip(1);
+    let gib: f64 = args.next().map(|s| s.parse()).transpose()?.unwrap_or(2.0);
+    let path = args
+        .next()
+        .unwrap_or_else(|| "target/day-1-bench.txt".to_owned());
+    let wanted = (gib * (1 << 30) as f64) as u64;
+
+    let expected = match std::fs::metadata(&path) {
+        Ok(meta) if meta.len() >= wanted && meta.len() < wanted + 128 => None,
+        _ => {
+            println!("Generating {gib} GiB of input in {path}");
+            Some(generate(&path, wanted)?)
+        }
+    };
+    let len = std::fs::metadata(&path)?.len();
+
+    let streamed = report("streaming", len, || {
+        part1_reader(BufReader::with_capacity(1 << 16, File::open(&path)?))
+    })?;
+    let mut threads = vec![2, std::thread::available_parallelism()?.get()];
+    threads.sort();
+    threads.dedup();
+    for n in threads {
+        let sum = report(&format!("parallel/{n}"), len, || part1_parallel(&path, n))?;
+        assert_eq!(streamed, sum, "{n} threads disagree with streaming");
+    }
+    if let Some(expected) = expected {
+        assert_eq!(expected, streamed, "sum doesn't match the generator");
+    }
+    Ok(())
+}
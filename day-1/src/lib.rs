@@ -0,0 +1,904 @@
+#![feature(ascii_char)]
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use anyhow::{format_err, Error};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use std::{ascii::Char as AsciiChar, str::FromStr, sync::LazyLock};
+
+mod stream;
+
+pub use stream::{part1_parallel, part1_reader};
+
+fn char_to_number(c: &AsciiChar) -> Option<u8> {
+    let v = *c as u8;
+
+    if v >= 48 && v <= 57 {
+        Some(v - 48)
+    } else {
+        None
+    }
+}
+
+/// The zero of every run of decimal digits in Unicode (general category Nd),
+/// as of Unicode 15. Each run is the digits 0 to 9 in order.
+const UNICODE_ZEROS: [u32; 68] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0,
+    0x11650, 0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x11F50, 0x16A60,
+    0x16AC0, 0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0,
+    0x1E950, 0x1FBF0,
+];
+
+fn unicode_digit(c: char) -> Option<u8> {
+    let c = u32::from(c);
+    let idx = UNICODE_ZEROS
+        .partition_point(|zero| *zero <= c)
+        .checked_sub(1)?;
+    let offset = c - UNICODE_ZEROS[idx];
+    (offset < 10).then_some(offset as u8)
+}
+
+fn calibration_value(line: &str) -> Result<u64, Error> {
+    let (first, last) = match line.as_ascii() {
+        Some(ascii) => {
+            // find the first number from the front
+            let first = ascii
+                .iter()
+                .find_map(char_to_number)
+                .ok_or_else(|| format_err!("doesn't contain a number"))?;
+
+            // We know that there's at least one number
+            (first, ascii.iter().rev().find_map(char_to_number).unwrap())
+        }
+        None => {
+            let first = line
+                .chars()
+                .find_map(unicode_digit)
+                .ok_or_else(|| format_err!("doesn't contain a number"))?;
+            (first, line.chars().rev().find_map(unicode_digit).unwrap())
+        }
+    };
+
+    Ok(first as u64 * 10 + last as u64)
+}
+
+pub fn part1(input: &str) -> Result<u64, Error> {
+    input.lines().map(calibration_value).sum()
+}
+
+/// A line the lenient parts left out of the sum, numbered from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedLine {
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LenientSum {
+    pub sum: u64,
+    pub skipped: Vec<SkippedLine>,
+}
+
+fn lenient_sum(input: &str, value: impl Fn(&str) -> Result<u64, Error>) -> LenientSum {
+    let mut res = LenientSum::default();
+    for (idx, line) in input.lines().enumerate() {
+        match value(line) {
+            Ok(num) => res.sum += num,
+            Err(e) => res.skipped.push(SkippedLine {
+                line: idx + 1,
+                reason: e.to_string(),
+            }),
+        }
+    }
+    res
+}
+
+/// Like `part1`, but skips lines without a number instead of failing.
+pub fn part1_lenient(input: &str) -> LenientSum {
+    lenient_sum(input, calibration_value)
+}
+
+/// Lowercases `s` and strips its diacritics, so "Zéro" and "ZERO" both become
+/// "zero". Also returns, for each byte of the folded text, the byte range of
+/// the character in `s` it came from.
+fn fold(s: &str) -> (String, Vec<(usize, usize)>) {
+    let mut folded = String::with_capacity(s.len());
+    let mut origins = Vec::with_capacity(s.len());
+    for (idx, c) in s.char_indices() {
+        for f in c.to_lowercase().nfd().filter(|f| !is_combining_mark(*f)) {
+            folded.push(f);
+            origins.resize(folded.len(), (idx, idx + c.len_utf8()));
+        }
+    }
+    (folded, origins)
+}
+
+/// Maps words to the digits they spell out, ignoring case and diacritics.
+/// Digits written as digits, in any script, are always recognised, whatever
+/// the words.
+pub struct Vocabulary {
+    words: Vec<(String, u8)>,
+    automaton: AhoCorasick,
+}
+
+const ENGLISH: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const SPANISH: [&str; 10] = [
+    "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+];
+const GERMAN: [&str; 10] = [
+    "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+];
+const FRENCH: [&str; 10] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+];
+
+impl Vocabulary {
+    pub fn new(words: impl IntoIterator<Item = (String, u8)>) -> Result<Self, Error> {
+        let words: Vec<(String, u8)> = words.into_iter().collect();
+        let folded: Vec<String> = words.iter().map(|(w, _)| fold(w).0).collect();
+        if let Some(((word, digit), _)) = words
+            .iter()
+            .zip(folded.iter())
+            .find(|((_, d), f)| *d > 9 || f.is_empty())
+        {
+            return Err(format_err!("Can't spell {digit} as {word:?}"));
+        }
+        let digits = (0..=9).map(|d| d.to_string());
+        let automaton = AhoCorasickBuilder::new()
+            .ascii_case_insensitive(true)
+            .build(folded.into_iter().chain(digits))?;
+        Ok(Self { words, automaton })
+    }
+
+    fn from_digit_words(words: [&str; 10]) -> Self {
+        Self::new((0..).zip(words).map(|(d, w)| (w.to_owned(), d))).unwrap()
+    }
+
+    /// The puzzle's words, "one" to "nine". It has no "zero", so `zero1`
+    /// reads as 11.
+    pub fn english() -> Self {
+        Self::new((1..).zip(&ENGLISH[1..]).map(|(d, w)| (w.to_string(), d))).unwrap()
+    }
+
+    /// One of the built in vocabularies, by language name or code.
+    /// `english-zero` is `english` with "zero" as well.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let words = match name {
+            "english" | "en" => return Some(Self::english()),
+            "english-zero" | "en0" => ENGLISH,
+            "spanish" | "es" => SPANISH,
+            "german" | "de" => GERMAN,
+            "french" | "fr" => FRENCH,
+            _ => return None,
+        };
+        Some(Self::from_digit_words(words))
+    }
+
+    /// Loads a custom word list, one `<word> <digit>` per line.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?.parse()
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                let (word, digit) = l
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .ok_or(format_err!("Expected <word> <digit>: {l}"))?;
+                Ok((word.to_owned(), digit.trim().parse()?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Self::new(words)
+    }
+}
+
+/// A digit found in a line, either written as a digit or spelled out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DigitMatch {
+    pub start: usize,
+    pub end: usize,
+    pub digit: u8,
+    pub spelled: bool,
+}
+
+impl DigitMatch {
+    fn from_pattern(vocab: &Vocabulary, m: aho_corasick::Match) -> Self {
+        let (digit, spelled) = match vocab.words.get(m.pattern().as_usize()) {
+            Some((_, digit)) => (*digit, true),
+            None => ((m.pattern().as_usize() - vocab.words.len()) as u8, false),
+        };
+        DigitMatch {
+            start: m.start(),
+            end: m.end(),
+            digit,
+            spelled,
+        }
+    }
+}
+
+/// Finds every digit in `line` in one pass, including spelled words that
+/// overlap like "twone". Matches come out ordered by where they end, with
+/// byte positions into `line`.
+fn find_num_or_written(line: &str, vocab: &Vocabulary) -> Vec<DigitMatch> {
+    if line.is_ascii() {
+        return vocab
+            .automaton
+            .find_overlapping_iter(line)
+            .map(|m| DigitMatch::from_pattern(vocab, m))
+            .collect();
+    }
+
+    let (folded, origins) = fold(line);
+    let mut matches: Vec<DigitMatch> = vocab
+        .automaton
+        .find_overlapping_iter(&folded)
+        .map(|m| {
+            let m = DigitMatch::from_pattern(vocab, m);
+            DigitMatch {
+                start: origins[m.start].0,
+                end: origins[m.end - 1].1,
+                ..m
+            }
+        })
+        .collect();
+    matches.extend(line.char_indices().filter_map(|(idx, c)| {
+        let digit = unicode_digit(c).filter(|_| !c.is_ascii_digit())?;
+        Some(DigitMatch {
+            start: idx,
+            end: idx + c.len_utf8(),
+            digit,
+            spelled: false,
+        })
+    }));
+    matches.sort_by_key(|m| (m.end, m.start));
+    matches
+}
+
+fn calibration_value_written(line: &str, vocab: &Vocabulary) -> Result<u64, Error> {
+    let matches = find_num_or_written(line, vocab);
+    let first = matches
+        .iter()
+        .min_by_key(|m| m.start)
+        .ok_or_else(|| format_err!("couldn't find a number (or written)"))?;
+
+    // We know that there's at least one number
+    let last = matches.iter().max_by_key(|m| m.start).unwrap();
+
+    Ok(first.digit as u64 * 10 + last.digit as u64)
+}
+
+pub fn part2(input: &str, vocab: &Vocabulary) -> Result<u64, Error> {
+    let v = input
+        .lines()
+        .map(|line| calibration_value_written(line, vocab))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(v.iter().sum())
+}
+
+/// Like `part2`, but skips lines without a number instead of failing.
+pub fn part2_lenient(input: &str, vocab: &Vocabulary) -> LenientSum {
+    lenient_sum(input, |line| calibration_value_written(line, vocab))
+}
+
+/// How the numbers found in a line make up its calibration value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combine {
+    /// The first and last digit, as in part 2.
+    FirstLastDigit,
+    /// The first and last whole number written next to each other, so "one"
+    /// and "twentythree" make 123.
+    FirstLastNumber,
+    /// Every number in the line added up.
+    Sum,
+}
+
+impl FromStr for Combine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => Ok(Self::FirstLastDigit),
+            "numbers" => Ok(Self::FirstLastNumber),
+            "sum" => Ok(Self::Sum),
+            _ => Err(format_err!("Expected digits, numbers or sum: {s}")),
+        }
+    }
+}
+
+/// One word of an English number phrase.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberWord {
+    Unit(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Thousand,
+}
+
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Matches the words of English number phrases, preferring "seventeen" over
+/// "seven" and "sixty" over "six".
+static NUMBER_WORDS: LazyLock<(AhoCorasick, Vec<NumberWord>)> = LazyLock::new(|| {
+    let words = (0..)
+        .zip(ENGLISH)
+        .map(|(n, w)| (w, NumberWord::Unit(n)))
+        .chain((10..).zip(TEENS).map(|(n, w)| (w, NumberWord::Teen(n))))
+        .chain((2..).zip(TENS).map(|(n, w)| (w, NumberWord::Tens(n * 10))))
+        .chain([
+            ("hundred", NumberWord::Hundred),
+            ("thousand", NumberWord::Thousand),
+        ]);
+    let (patterns, kinds): (Vec<_>, Vec<_>) = words.unzip();
+    let automaton = AhoCorasickBuilder::new()
+        .match_kind(MatchKind::LeftmostLongest)
+        .build(patterns)
+        .unwrap();
+    (automaton, kinds)
+});
+
+/// Reads a number below a hundred from the start of `words`, returning it and
+/// how many words it took.
+fn below_hundred(words: &[NumberWord]) -> Option<(u64, usize)> {
+    use NumberWord::*;
+
+    match words {
+        [Teen(n), ..] => Some((*n, 1)),
+        [Tens(t), Unit(u), ..] if *u > 0 => Some((t + u, 2)),
+        [Tens(t), ..] => Some((*t, 1)),
+        [Unit(u), ..] if *u > 0 => Some((*u, 1)),
+        _ => None,
+    }
+}
+
+fn below_thousand(words: &[NumberWord]) -> Option<(u64, usize)> {
+    match words {
+        [NumberWord::Unit(u), NumberWord::Hundred, rest @ ..] if *u > 0 => {
+            let (rest, len) = below_hundred(rest).unwrap_or((0, 0));
+            Some((u * 100 + rest, 2 + len))
+        }
+        _ => below_hundred(words),
+    }
+}
+
+/// Reads the longest number phrase from the start of `words`, so that
+/// "twentyone" is 21 but "onetwo" is just 1.
+fn read_number(words: &[NumberWord]) -> Option<(u64, usize)> {
+    if let [NumberWord::Unit(0), ..] = words {
+        return Some((0, 1));
+    }
+    let (n, len) = below_thousand(words)?;
+    if words.get(len) != Some(&NumberWord::Thousand) {
+        return Some((n, len));
+    }
+    let (rest, rest_len) = below_thousand(&words[len + 1..]).unwrap_or((0, 0));
+    Some((n * 1000 + rest, len + 1 + rest_len))
+}
+
+/// A whole number found in a line, with byte positions into the line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberMatch {
+    pub start: usize,
+    pub end: usize,
+    pub value: u64,
+}
+
+/// Finds every number in `line`: runs of digits, and English number phrases
+/// written without spaces, ignoring case and diacritics. Matches don't
+/// overlap and come out in order.
+fn find_numbers(line: &str) -> Result<Vec<NumberMatch>, Error> {
+    let too_big = || format_err!("number too big");
+    let mut numbers = Vec::new();
+
+    let mut run: Option<NumberMatch> = None;
+    for (idx, c) in line.char_indices() {
+        match (unicode_digit(c), run.as_mut()) {
+            (Some(d), Some(number)) => {
+                number.value = number
+                    .value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(d as u64))
+                    .ok_or_else(too_big)?;
+                number.end = idx + c.len_utf8();
+            }
+            (Some(d), None) => {
+                run = Some(NumberMatch {
+                    start: idx,
+                    end: idx + c.len_utf8(),
+                    value: d as u64,
+                })
+            }
+            (None, _) => numbers.extend(run.take()),
+        }
+    }
+    numbers.extend(run);
+
+    let (folded, origins) = fold(line);
+    let (automaton, kinds) = &*NUMBER_WORDS;
+    let found: Vec<_> = automaton.find_iter(&folded).collect();
+    // Words only make one phrase when nothing comes between them.
+    for phrase in found.chunk_by(|a, b| a.end() == b.start()) {
+        let words: Vec<NumberWord> = phrase
+            .iter()
+            .map(|m| kinds[m.pattern().as_usize()])
+            .collect();
+        let mut idx = 0;
+        while idx < words.len() {
+            let Some((value, len)) = read_number(&words[idx..]) else {
+                idx += 1;
+                continue;
+            };
+            numbers.push(NumberMatch {
+                start: origins[phrase[idx].start()].0,
+                end: origins[phrase[idx + len - 1].end() - 1].1,
+                value,
+            });
+            idx += len;
+        }
+    }
+    numbers.sort_by_key(|n| n.start);
+    Ok(numbers)
+}
+
+/// `first` with `last` written after it, so 1 and 23 make 123.
+fn concat(first: u64, last: u64) -> Option<u64> {
+    let shift = 10u64.checked_pow(last.checked_ilog10().unwrap_or(0) + 1)?;
+    first.checked_mul(shift)?.checked_add(last)
+}
+
+fn calibration_value_combined(
+    line: &str,
+    vocab: &Vocabulary,
+    combine: Combine,
+) -> Result<u64, Error> {
+    if combine == Combine::FirstLastDigit {
+        return calibration_value_written(line, vocab);
+    }
+    let numbers = find_numbers(line)?;
+    let (first, last) = match (numbers.first(), numbers.last()) {
+        (Some(first), Some(last)) => (first.value, last.value),
+        _ => return Err(format_err!("couldn't find a number (or written)")),
+    };
+    match combine {
+        Combine::FirstLastNumber => concat(first, last),
+        _ => numbers
+            .iter()
+            .try_fold(0u64, |sum, n| sum.checked_add(n.value)),
+    }
+    .ok_or_else(|| format_err!("number too big"))
+}
+
+/// Like `part2`, but reading whole numbers like "twentyone" when `combine`
+/// asks for them. `vocab` only applies to `Combine::FirstLastDigit`; whole
+/// numbers are read in English whatever it is.
+pub fn part2_combined(input: &str, vocab: &Vocabulary, combine: Combine) -> Result<u64, Error> {
+    input
+        .lines()
+        .map(|line| calibration_value_combined(line, vocab, combine))
+        .sum()
+}
+
+/// Like `part2_combined`, but skips lines without a number instead of
+/// failing.
+pub fn part2_combined_lenient(input: &str, vocab: &Vocabulary, combine: Combine) -> LenientSum {
+    lenient_sum(input, |line| {
+        calibration_value_combined(line, vocab, combine)
+    })
+}
+
+fn push_html_escaped(out: &mut String, c: char) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        c => out.push(c),
+    }
+}
+
+/// What part 2 read from one line, numbered from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineExplanation<'a> {
+    pub line: usize,
+    pub text: &'a str,
+    pub matches: Vec<DigitMatch>,
+    pub first: Option<DigitMatch>,
+    pub last: Option<DigitMatch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Highlight {
+    Ansi,
+    Html,
+}
+
+/// How a byte of an explained line gets highlighted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mark {
+    Plain,
+    /// Part of a spelled word that isn't the first or last match.
+    Spelled,
+    /// Part of the first or last match.
+    Chosen,
+}
+
+impl DigitMatch {
+    fn describe(&self, text: &str) -> String {
+        let source = if self.spelled {
+            format!("spelled {:?}", &text[self.start..self.end])
+        } else {
+            String::from("digit")
+        };
+        format!("{} at {}..{} ({source})", self.digit, self.start, self.end)
+    }
+}
+
+impl LineExplanation<'_> {
+    pub fn value(&self) -> Option<u64> {
+        Some(self.first?.digit as u64 * 10 + self.last?.digit as u64)
+    }
+
+    fn mark_at(&self, idx: usize) -> Mark {
+        let within = |m: &DigitMatch| m.start <= idx && idx < m.end;
+        if self.first.iter().chain(self.last.iter()).any(within) {
+            Mark::Chosen
+        } else if self.matches.iter().any(|m| m.spelled && within(m)) {
+            Mark::Spelled
+        } else {
+            Mark::Plain
+        }
+    }
+
+    /// The line's text with the first and last matches and any other spelled
+    /// words marked up.
+    pub fn highlight(&self, highlight: Highlight) -> String {
+        let mut out = String::new();
+        let mut current = Mark::Plain;
+        for (idx, c) in self.text.char_indices() {
+            let mark = self.mark_at(idx);
+            if mark != current {
+                out.push_str(match (highlight, current) {
+                    (_, Mark::Plain) => "",
+                    (Highlight::Ansi, _) => "\x1b[0m",
+                    (Highlight::Html, _) => "</span>",
+                });
+                out.push_str(match (highlight, mark) {
+                    (_, Mark::Plain) => "",
+                    (Highlight::Ansi, Mark::Spelled) => "\x1b[36m",
+                    (Highlight::Ansi, Mark::Chosen) => "\x1b[1;33m",
+                    (Highlight::Html, Mark::Spelled) => "<span class=\"spelled\">",
+                    (Highlight::Html, Mark::Chosen) => "<span class=\"chosen\">",
+                });
+                current = mark;
+            }
+            match highlight {
+                Highlight::Ansi => out.push(c),
+                Highlight::Html => push_html_escaped(&mut out, c),
+            }
+        }
+        out.push_str(match (highlight, current) {
+            (_, Mark::Plain) => "",
+            (Highlight::Ansi, _) => "\x1b[0m",
+            (Highlight::Html, _) => "</span>",
+        });
+        out
+    }
+
+    /// One line summary of the first and last matches and the value.
+    pub fn summary(&self) -> String {
+        match (self.first, self.last, self.value()) {
+            (Some(first), Some(last), Some(value)) => format!(
+                "{value}: first {}, last {}",
+                first.describe(self.text),
+                last.describe(self.text)
+            ),
+            _ => String::from("no number (or written)"),
+        }
+    }
+}
+
+/// Shows what part 2 makes of each line, built on the positions that
+/// `find_num_or_written` reports.
+pub fn explain<'a>(input: &'a str, vocab: &Vocabulary) -> Vec<LineExplanation<'a>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, text)| {
+            let matches = find_num_or_written(text, vocab);
+            LineExplanation {
+                line: idx + 1,
+                text,
+                first: matches.iter().min_by_key(|m| m.start).copied(),
+                last: matches.iter().max_by_key(|m| m.start).copied(),
+                matches,
+            }
+        })
+        .collect()
+}
+
+/// A standalone HTML page explaining every line.
+pub fn explain_html(input: &str, vocab: &Vocabulary) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Calibration explained</title>\n\
+         <style>\n.spelled { background: #cdeffa; }\n.chosen { background: #ffe08a; font-weight: bold; }\n\
+         td { font-family: monospace; padding: 0 1em; }\n</style>\n</head>\n<body>\n<table>\n",
+    );
+    for line in explain(input, vocab) {
+        let mut summary = String::new();
+        line.summary()
+            .chars()
+            .for_each(|c| push_html_escaped(&mut summary, c));
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{summary}</td></tr>\n",
+            line.line,
+            line.highlight(Highlight::Html),
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_problem() {
+        let s = r#"1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet"#;
+        assert_eq!(part1(s).unwrap(), 142);
+    }
+
+    #[test]
+    fn from_problem_p2() {
+        let s = r#"two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen"#;
+        assert_eq!(part2(s, &Vocabulary::english()).unwrap(), 281);
+    }
+
+    #[test]
+    fn lenient() {
+        let s = "1abc2\nnothing here\n\ntreb7uchet\nfour";
+        assert!(part1(s).is_err());
+        assert_eq!(
+            LenientSum {
+                sum: 12 + 77,
+                skipped: vec![
+                    SkippedLine {
+                        line: 2,
+                        reason: String::from("doesn't contain a number")
+                    },
+                    SkippedLine {
+                        line: 3,
+                        reason: String::from("doesn't contain a number")
+                    },
+                    SkippedLine {
+                        line: 5,
+                        reason: String::from("doesn't contain a number")
+                    },
+                ]
+            },
+            part1_lenient(s)
+        );
+
+        let res = part2_lenient(s, &Vocabulary::english());
+        assert_eq!(12 + 77 + 44, res.sum);
+        assert_eq!(
+            vec![2, 3],
+            res.skipped.iter().map(|s| s.line).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn explained() {
+        let english = Vocabulary::english();
+        let lines = explain("xtwone3four\nnope\n7pqrstsixteen", &english);
+        assert_eq!(3, lines.len());
+
+        assert_eq!(Some(24), lines[0].value());
+        assert_eq!(
+            "24: first 2 at 1..4 (spelled \"two\"), last 4 at 7..11 (spelled \"four\")",
+            lines[0].summary()
+        );
+        assert_eq!(
+            "x\x1b[1;33mtwo\x1b[0m\x1b[36mne\x1b[0m3\x1b[1;33mfour\x1b[0m",
+            lines[0].highlight(Highlight::Ansi)
+        );
+        assert_eq!(
+            "x<span class=\"chosen\">two</span><span class=\"spelled\">ne</span>3<span class=\"chosen\">four</span>",
+            lines[0].highlight(Highlight::Html)
+        );
+
+        assert_eq!(None, lines[1].value());
+        assert_eq!("nope", lines[1].highlight(Highlight::Ansi));
+
+        assert_eq!(
+            "76: first 7 at 0..1 (digit), last 6 at 6..9 (spelled \"six\")",
+            lines[2].summary()
+        );
+
+        let html = explain_html("a<b>one", &english);
+        assert!(html.contains("a&lt;b&gt;<span class=\"chosen\">one</span>"));
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let english = Vocabulary::english();
+        let matches = find_num_or_written("xtwone3", &english);
+        assert_eq!(
+            vec![
+                DigitMatch {
+                    start: 1,
+                    end: 4,
+                    digit: 2,
+                    spelled: true
+                },
+                DigitMatch {
+                    start: 3,
+                    end: 6,
+                    digit: 1,
+                    spelled: true
+                },
+                DigitMatch {
+                    start: 6,
+                    end: 7,
+                    digit: 3,
+                    spelled: false
+                },
+            ],
+            matches
+        );
+        assert_eq!(part2("xtwone", &english).unwrap(), 21);
+        assert_eq!(part2("zeroneightzero", &english).unwrap(), 18);
+        assert_eq!(part2("zero1", &english).unwrap(), 11);
+        let with_zero = Vocabulary::builtin("english-zero").unwrap();
+        assert_eq!(part2("zeroneightzero", &with_zero).unwrap(), 0);
+        assert_eq!(part2("zero1", &with_zero).unwrap(), 1);
+    }
+
+    #[test]
+    fn unicode_digits() {
+        assert_eq!(Some(3), unicode_digit('٣'));
+        assert_eq!(Some(9), unicode_digit('９'));
+        assert_eq!(Some(0), unicode_digit('0'));
+        assert_eq!(None, unicode_digit('a'));
+        assert_eq!(None, unicode_digit('\u{065F}'));
+
+        assert_eq!(part1("٣abc٧").unwrap(), 37);
+        assert_eq!(part1("ab１２ü").unwrap(), 12);
+        assert_eq!(part1("٤ab5\n1abc2").unwrap(), 45 + 12);
+        assert!(part1("äbc").is_err());
+    }
+
+    #[test]
+    fn folded_words() {
+        let english = Vocabulary::english();
+        assert_eq!(part2("xONEtwo", &english).unwrap(), 12);
+        assert_eq!(part2("ÓnE٧", &english).unwrap(), 17);
+
+        // Positions point into the original text, accents and all.
+        let matches = find_num_or_written("xÉIGHT", &english);
+        assert_eq!(
+            vec![DigitMatch {
+                start: 1,
+                end: 7,
+                digit: 8,
+                spelled: true
+            }],
+            matches
+        );
+
+        let german = Vocabulary::builtin("german").unwrap();
+        assert_eq!(part2("FUNFundzwei", &german).unwrap(), 52);
+        let french = Vocabulary::builtin("french").unwrap();
+        assert_eq!(part2("ZERO２Zéro", &french).unwrap(), 0);
+    }
+
+    #[test]
+    fn other_vocabularies() {
+        let german = Vocabulary::builtin("de").unwrap();
+        assert_eq!(part2("xdreinullfünfy", &german).unwrap(), 35);
+        let spanish = Vocabulary::builtin("spanish").unwrap();
+        assert_eq!(part2("ochotwo7", &spanish).unwrap(), 87);
+        assert!(Vocabulary::builtin("klingon").is_none());
+
+        let custom: Vocabulary = "won 1\ntoo 2\n\nate 8\n".parse().unwrap();
+        assert_eq!(part2("toowonate", &custom).unwrap(), 28);
+        assert!("ten 10".parse::<Vocabulary>().is_err());
+        assert!("ten".parse::<Vocabulary>().is_err());
+    }
+
+    #[test]
+    fn compound_numbers() {
+        let numbers = |line| {
+            find_numbers(line)
+                .unwrap()
+                .iter()
+                .map(|n| n.value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![21, 99, 100],
+            numbers("twentyonexninetynine.onehundred")
+        );
+        assert_eq!(vec![1, 2, 17, 60], numbers("onetwoseventeensixty"));
+        assert_eq!(vec![342, 1015], numbers("threehundredfortytwo1015"));
+        assert_eq!(vec![2021], numbers("twothousandtwentyone"));
+        assert_eq!(vec![0, 100], numbers("zeroonehundredxhundred"));
+        assert_eq!(vec![45], numbers("FORTYFÏVE"));
+        assert!(numbers("nothing").is_empty());
+
+        let m = find_numbers("xTwentyOne").unwrap();
+        assert_eq!(
+            NumberMatch {
+                start: 1,
+                end: 10,
+                value: 21
+            },
+            m[0]
+        );
+    }
+
+    #[test]
+    fn combined() {
+        let english = Vocabulary::english();
+        let s = "twentyone3ninetynine\nonehundred";
+        assert_eq!(
+            19 + 11,
+            part2_combined(s, &english, Combine::FirstLastDigit).unwrap()
+        );
+        assert_eq!(
+            2199 + 100100,
+            part2_combined(s, &english, Combine::FirstLastNumber).unwrap()
+        );
+        assert_eq!(
+            21 + 3 + 99 + 100,
+            part2_combined(s, &english, Combine::Sum).unwrap()
+        );
+        assert!(part2_combined("nope", &english, Combine::Sum).is_err());
+        assert!(part2_combined("99999999999999999999", &english, Combine::Sum).is_err());
+        assert_eq!(Ok(Combine::Sum), "sum".parse().map_err(|_: Error| ()));
+
+        let res = part2_combined_lenient(&format!("nope\n{s}"), &english, Combine::Sum);
+        assert_eq!(21 + 3 + 99 + 100, res.sum);
+        assert_eq!(
+            vec![1],
+            res.skipped.iter().map(|s| s.line).collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,123 +1,95 @@
-#![feature(ascii_char)]
-
 use anyhow::{format_err, Error};
 
-use std::ascii::Char as AsciiChar;
-
-fn main() -> Result<(), Error> {
-    let input = include_str!("../input.txt");
-
-    println!("Part 1 result: {}", part1(input)?);
-
-    println!("Part 2 result: {}", part2(input)?);
-
-    Ok(())
-}
-
-fn char_to_number(c: &AsciiChar) -> Option<u8> {
-    let v = *c as u8;
-
-    if v >= 48 && v <= 57 {
-        Some(v - 48)
-    } else {
-        None
+use day_1::{
+    explain, explain_html, part1, part1_lenient, part1_parallel, part2_combined,
+    part2_combined_lenient, Combine, Highlight, LenientSum, Vocabulary,
+};
+
+fn print_lenient(part: usize, res: &LenientSum) {
+    println!(
+        "Part {part} result: {} ({} lines skipped)",
+        res.sum,
+        res.skipped.len()
+    );
+    if res.skipped.is_empty() {
+        return;
     }
-}
-
-fn part1(input: &str) -> Result<u64, Error> {
-    let mut v = Vec::new();
-    for line in input.lines() {
-        let ascii = line.as_ascii().ok_or(format_err!("input is not ascii"))?;
-
-        // find the first number from the front
-        let first = ascii
-            .iter()
-            .find_map(char_to_number)
-            .ok_or(format_err!("doesn't contain a number"))?;
-
-        // We know that there's at least one number
-        let last = ascii.iter().rev().find_map(char_to_number).unwrap();
-
-        let num = first as u64 * 10 + last as u64;
-        v.push(num);
+    println!("{:>8} | reason", "line");
+    for skipped in res.skipped.iter() {
+        println!("{:>8} | {}", skipped.line, skipped.reason);
     }
-
-    Ok(v.iter().sum())
 }
 
-fn find_num_or_written(line: &[AsciiChar], start_idx: usize) -> Option<u8> {
-    if let Some(num) = char_to_number(&line[start_idx]) {
-        return Some(num);
-    }
+fn main() -> Result<(), Error> {
+    let input = include_str!("../input.txt");
 
-    let s = line[start_idx..].as_str();
-    if s.starts_with("one") {
-        return Some(1);
-    } else if s.starts_with("two") {
-        return Some(2);
-    } else if s.starts_with("three") {
-        return Some(3);
-    } else if s.starts_with("four") {
-        return Some(4);
-    } else if s.starts_with("five") {
-        return Some(5);
-    } else if s.starts_with("six") {
-        return Some(6);
-    } else if s.starts_with("seven") {
-        return Some(7);
-    } else if s.starts_with("eight") {
-        return Some(8);
-    } else if s.starts_with("nine") {
-        return Some(9);
+    // Spelled digits default to English, or name a language or a word list.
+    // With --lenient, lines without a number are skipped and listed.
+    // --explain and --explain-html show what part 2 read from each line.
+    // --stream <path> runs part 1 over a file too large to hold in memory.
+    // --combine digits|numbers|sum picks how part 2 reads a line.
+    let mut lenient = false;
+    let mut combine = Combine::FirstLastDigit;
+    let mut explain_as = None;
+    let mut vocab = Vocabulary::english();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lenient" => lenient = true,
+            "--explain" => explain_as = Some(Highlight::Ansi),
+            "--explain-html" => explain_as = Some(Highlight::Html),
+            "--stream" => {
+                let path = args.next().ok_or(format_err!("--stream needs a path"))?;
+                let threads = std::thread::available_parallelism()?.get();
+                println!("Part 1 result: {}", part1_parallel(&path, threads)?);
+                return Ok(());
+            }
+            "--combine" => {
+                combine = args
+                    .next()
+                    .ok_or_else(|| format_err!("--combine needs a rule"))?
+                    .parse()?;
+            }
+            _ if arg.starts_with("--") => return Err(format_err!("Unknown argument {arg}")),
+            _ => {}
+        }
+        if arg.starts_with("--") {
+            continue;
+        }
+        vocab = match Vocabulary::builtin(&arg) {
+            Some(vocab) => vocab,
+            None => Vocabulary::load(&arg).map_err(|e| format_err!("{arg}: {e}"))?,
+        };
     }
-    None
-}
-
-fn part2(input: &str) -> Result<u64, Error> {
-    let mut v = Vec::new();
-    for line in input.lines() {
-        let ascii = line.as_ascii().ok_or(format_err!("input is not ascii"))?;
-
-        let first = (0..ascii.len())
-            .find_map(|i| find_num_or_written(ascii, i))
-            .ok_or(format_err!("couldn't find a number (or written)"))?;
 
-        let last = (0..ascii.len())
-            .rev()
-            .find_map(|i| find_num_or_written(ascii, i))
-            .unwrap();
-
-        let num = first as u64 * 10 + last as u64;
-        v.push(num);
+    match explain_as {
+        Some(Highlight::Ansi) => {
+            for line in explain(input, &vocab) {
+                println!(
+                    "{:>6}: {}\n        {}",
+                    line.line,
+                    line.highlight(Highlight::Ansi),
+                    line.summary()
+                );
+            }
+            return Ok(());
+        }
+        Some(Highlight::Html) => {
+            print!("{}", explain_html(input, &vocab));
+            return Ok(());
+        }
+        None => {}
     }
 
-    Ok(v.iter().sum())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    if lenient {
+        print_lenient(1, &part1_lenient(input));
+        print_lenient(2, &part2_combined_lenient(input, &vocab, combine));
+        return Ok(());
+    }
 
-    use pretty_assertions::assert_eq;
+    println!("Part 1 result: {}", part1(input)?);
 
-    #[test]
-    fn from_problem() {
-        let s = r#"1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet"#;
-        assert_eq!(part1(s).unwrap(), 142);
-    }
+    println!("Part 2 result: {}", part2_combined(input, &vocab, combine)?);
 
-    #[test]
-    fn from_problem_p2() {
-        let s = r#"two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen"#;
-        assert_eq!(part2(s).unwrap(), 281);
-    }
+    Ok(())
 }
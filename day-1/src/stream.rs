@@ -0,0 +1,241 @@
+//! Part 1 over inputs too large to hold in memory: read line by line from any
+//! `BufRead`, or split a file into chunks and sum them on several threads.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom},
+    path::Path,
+    thread,
+};
+
+use anyhow::{format_err, Error};
+use memchr::memchr;
+
+use crate::calibration_value;
+
+/// How many bytes are checked for a digit at once. Checking a whole block
+/// without branching lets the compiler vectorise the scan; 16 bytes fill one
+/// SSE register without scanning far past a digit near the start of a line.
+const BLOCK: usize = 16;
+
+/// Read buffer for each chunk of the parallel mode.
+const CHUNK_BUFFER: usize = 1 << 16;
+
+/// Digits and the bytes of non-ASCII characters, which might be digits too.
+fn stops_scan(b: u8) -> bool {
+    (b.wrapping_sub(b'0') < 10) | (b >= 0x80)
+}
+
+fn has_stop(block: &[u8]) -> bool {
+    block.iter().fold(false, |acc, &b| acc | stops_scan(b))
+}
+
+fn first_stop(line: &[u8]) -> Option<u8> {
+    let block = line.chunks(BLOCK).find(|block| has_stop(block))?;
+    block.iter().copied().find(|b| stops_scan(*b))
+}
+
+fn last_stop(line: &[u8]) -> Option<u8> {
+    let block = line.rchunks(BLOCK).find(|block| has_stop(block))?;
+    block.iter().copied().rev().find(|b| stops_scan(*b))
+}
+
+/// The calibration value of a line without its line ending. Lines with other
+/// characters before the first or after the last ASCII digit go through the
+/// `&str` path, which knows other digits.
+fn line_value(line: &[u8]) -> Result<u64, Error> {
+    match (first_stop(line), last_stop(line)) {
+        (None, _) => Err(format_err!("doesn't contain a number")),
+        (Some(first), Some(last)) if first.is_ascii() && last.is_ascii() => {
+            Ok((first - b'0') as u64 * 10 + (last - b'0') as u64)
+        }
+        _ => calibration_value(std::str::from_utf8(line)?),
+    }
+}
+
+/// Where a line that couldn't be read is, relative to the start of whatever
+/// was being summed.
+struct BadLine {
+    line: usize,
+    offset: u64,
+    err: Error,
+}
+
+/// Sums every line of `reader`. Lines are read in place from the reader's
+/// buffer; only a line cut off by the end of the buffer is copied, so memory
+/// use only grows with the longest line.
+fn sum_lines(reader: &mut impl BufRead) -> Result<u64, BadLine> {
+    let mut partial = Vec::new();
+    let (mut sum, mut line, mut offset) = (0, 0, 0);
+    let mut add = |text: &[u8], len: usize| {
+        line += 1;
+        let text = text.strip_suffix(b"\r").unwrap_or(text);
+        sum += line_value(text).map_err(|err| BadLine { line, offset, err })?;
+        offset += len as u64;
+        Ok(())
+    };
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => {
+                return Err(BadLine {
+                    line: line + 1,
+                    offset,
+                    err: e.into(),
+                })
+            }
+        };
+        if buf.is_empty() {
+            if !partial.is_empty() {
+                add(&partial, partial.len())?;
+            }
+            return Ok(sum);
+        }
+        let mut rest = buf;
+        while let Some(end) = memchr(b'\n', rest) {
+            if partial.is_empty() {
+                add(&rest[..end], end + 1)?;
+            } else {
+                partial.extend_from_slice(&rest[..end]);
+                add(&partial, partial.len() + 1)?;
+                partial.clear();
+            }
+            rest = &rest[end + 1..];
+        }
+        partial.extend_from_slice(rest);
+        let read = buf.len();
+        reader.consume(read);
+    }
+}
+
+/// Part 1 read one line at a time, for inputs that don't fit in memory.
+pub fn part1_reader(mut reader: impl BufRead) -> Result<u64, Error> {
+    sum_lines(&mut reader).map_err(|bad| format_err!("Line {}: {}", bad.line, bad.err))
+}
+
+/// Where the first line starting at or after `from` begins.
+fn next_line_start(file: &mut File, from: u64) -> Result<u64, Error> {
+    if from == 0 {
+        return Ok(0);
+    }
+    // Start one byte early, so a line that begins right at `from` is kept.
+    file.seek(SeekFrom::Start(from - 1))?;
+    let skipped = BufReader::new(file).skip_until(b'\n')?;
+    Ok(from - 1 + skipped as u64)
+}
+
+fn sum_chunk(path: &Path, start: u64, end: u64) -> Result<u64, Error> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut reader = BufReader::with_capacity(CHUNK_BUFFER, file.take(end - start));
+    // Lines can't be numbered without reading everything before the chunk,
+    // so point at the byte the bad line starts on instead.
+    sum_lines(&mut reader)
+        .map_err(|bad| format_err!("Line at byte {}: {}", start + bad.offset, bad.err))
+}
+
+/// Part 1 over the file at `path`, split at newlines into one chunk per
+/// thread.
+pub fn part1_parallel(path: impl AsRef<Path>, threads: usize) -> Result<u64, Error> {
+    let path = path.as_ref();
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let threads = threads.max(1) as u64;
+
+    let mut bounds = vec![0];
+    for idx in 1..threads {
+        let from = (len * idx / threads).max(*bounds.last().unwrap());
+        bounds.push(next_line_start(&mut file, from)?);
+    }
+    bounds.push(len);
+    // Lines longer than a chunk leave some chunks empty.
+    bounds.dedup();
+
+    thread::scope(|s| {
+        let handles: Vec<_> = bounds
+            .windows(2)
+            .map(|w| {
+                let (start, end) = (w[0], w[1]);
+                s.spawn(move || sum_chunk(path, start, end))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("calibration thread panicked"))
+            .sum()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const EXAMPLE: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    /// A file in the temp dir that's removed again when the test is done.
+    struct TempFile(std::path::PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("day-1-{}-{name}", std::process::id()));
+            std::fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn digits_across_blocks() {
+        let line = format!(
+            "{}7{}x{}3{}",
+            "a".repeat(40),
+            "b".repeat(31),
+            "c".repeat(5),
+            "d".repeat(33)
+        );
+        assert_eq!(73, line_value(line.as_bytes()).unwrap());
+        assert_eq!(55, line_value(b"5").unwrap());
+        assert!(line_value(&[b'z'; 100]).is_err());
+    }
+
+    #[test]
+    fn from_reader() {
+        assert_eq!(142, part1_reader(EXAMPLE.as_bytes()).unwrap());
+        assert_eq!(
+            142,
+            part1_reader(EXAMPLE.replace('\n', "\r\n").as_bytes()).unwrap()
+        );
+        assert_eq!(77, part1_reader("a٤b\nz3z\n".as_bytes()).unwrap());
+        assert_eq!(34, part1_reader("x3y٤z".as_bytes()).unwrap());
+        // Lines that don't fit in the reader's buffer get put back together.
+        let tiny = BufReader::with_capacity(3, EXAMPLE.as_bytes());
+        assert_eq!(142, part1_reader(tiny).unwrap());
+
+        let err = part1_reader("1abc2\nabc\n".as_bytes()).unwrap_err();
+        assert_eq!("Line 2: doesn't contain a number", err.to_string());
+    }
+
+    #[test]
+    fn in_chunks() {
+        let input = format!("{EXAMPLE}\n").repeat(25);
+        let file = TempFile::new("chunks.txt", &input);
+        for threads in [1, 2, 3, 7, 64, 1000] {
+            assert_eq!(142 * 25, part1_parallel(&file.0, threads).unwrap());
+        }
+
+        let file = TempFile::new("bad-chunk.txt", "12\n34\nnope\n56\n");
+        let err = part1_parallel(&file.0, 3).unwrap_err();
+        assert_eq!("Line at byte 6: doesn't contain a number", err.to_string());
+    }
+}
@@ -0,0 +1,482 @@
+#![feature(ascii_char)]
+
+use anyhow::{format_err, Error};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, digit1, multispace0, multispace1},
+    combinator::{all_consuming, map, map_res},
+    error::VerboseError,
+    multi::separated_list1,
+    sequence::{delimited, separated_pair},
+    Finish, IResult,
+};
+use thiserror::Error;
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+mod stats;
+
+pub use stats::{Draw, Simulation};
+
+pub type ParsedInput = Games;
+
+/// The sum of the numbers of the games that are possible with `bag`.
+pub fn part1(input: &ParsedInput, bag: &Bag) -> Result<usize, Error> {
+    Ok(part1_report(input, bag).sum)
+}
+
+/// Part 1, along with why each game left out of the sum was impossible.
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub sum: usize,
+    pub impossible: Vec<Impossible>,
+}
+
+pub fn part1_report(input: &ParsedInput, bag: &Bag) -> Report {
+    let mut report = Report {
+        sum: 0,
+        impossible: vec![],
+    };
+    for game in input.games.iter() {
+        match bag.check(game) {
+            Ok(()) => report.sum += game.number,
+            Err(impossible) => report.impossible.push(impossible),
+        }
+    }
+    report
+}
+
+/// The sum of the powers of each game's min set, over the colors in `bag`.
+pub fn part2(input: &ParsedInput, bag: &Bag) -> Result<usize, Error> {
+    let ok_games_sum = input.games.iter().map(|g| g.min_set().power(bag)).sum();
+    Ok(ok_games_sum)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    pub number: usize,
+    rounds: Vec<GameRound>,
+}
+
+impl Game {
+    /// The fewest cubes of each color that could have been in the bag, for
+    /// every color seen in any round.
+    fn min_set(&self) -> GameRound {
+        let mut cubes = BTreeMap::new();
+        for (color, num) in self.rounds.iter().flat_map(|r| r.cubes.iter()) {
+            let max = cubes.entry(color.clone()).or_insert(0);
+            *max = (*max).max(*num);
+        }
+        GameRound { cubes }
+    }
+}
+
+/// How many cubes of each color were shown, for whatever colors turned up.
+#[derive(Debug, Clone, PartialEq)]
+struct GameRound {
+    cubes: BTreeMap<String, usize>,
+}
+
+impl GameRound {
+    /// The product of the counts of each of the bag's colors, so a min set
+    /// without one of them has a power of 0.
+    fn power(&self, bag: &Bag) -> usize {
+        bag.cubes
+            .keys()
+            .map(|color| self.cubes.get(color).copied().unwrap_or(0))
+            .product()
+    }
+}
+
+impl FromStr for GameRound {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cubes = finish(
+            s,
+            all_consuming(delimited(multispace0, round, multispace0))(s),
+        )?;
+        GameRound::from_cubes(cubes)
+    }
+}
+
+/// Writes cube counts as `3 blue, 4 red`.
+fn write_cubes(
+    f: &mut std::fmt::Formatter<'_>,
+    cubes: &BTreeMap<String, usize>,
+) -> std::fmt::Result {
+    for (idx, (color, num)) in cubes.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{num} {color}")?;
+    }
+    Ok(())
+}
+
+impl Display for GameRound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cubes(f, &self.cubes)
+    }
+}
+
+impl Display for Game {
+    /// The canonical form of the game: single spaces, and the colors of each
+    /// round in alphabetical order.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Game {}: ", self.number)?;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{round}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What's in the bag the cubes are drawn from. There are no cubes of colors
+/// that aren't listed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bag {
+    cubes: BTreeMap<String, usize>,
+}
+
+impl Default for Bag {
+    /// The bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+    fn default() -> Self {
+        let cubes = [("red", 12), ("green", 13), ("blue", 14)]
+            .into_iter()
+            .map(|(color, num)| (color.to_owned(), num))
+            .collect();
+        Self { cubes }
+    }
+}
+
+/// Why a game couldn't have been played with a bag: the first round that
+/// showed more cubes of a color than the bag holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impossible {
+    pub game: usize,
+    /// Numbered from 1.
+    pub round: usize,
+    pub color: String,
+    pub shown: usize,
+    pub in_bag: usize,
+}
+
+impl Display for Impossible {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Game {} round {}: {} {} shown, but the bag only has {}",
+            self.game, self.round, self.shown, self.color, self.in_bag
+        )
+    }
+}
+
+impl Bag {
+    /// Checks every round of `game` could have been drawn from this bag.
+    pub fn check(&self, game: &Game) -> Result<(), Impossible> {
+        for (idx, round) in game.rounds.iter().enumerate() {
+            for (color, shown) in round.cubes.iter() {
+                let in_bag = self.cubes.get(color).copied().unwrap_or(0);
+                if *shown > in_bag {
+                    return Err(Impossible {
+                        game: game.number,
+                        round: idx + 1,
+                        color: color.clone(),
+                        shown: *shown,
+                        in_bag,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_possible(&self, game: &Game) -> bool {
+        self.check(game).is_ok()
+    }
+
+    /// Loads bags from a file, one per line written like a round:
+    /// `12 red, 13 green, 14 blue`. Blank lines and `#` comments are skipped.
+    pub fn load(path: &str) -> Result<Vec<Self>, Error> {
+        std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl FromStr for Bag {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let GameRound { cubes } = s.parse()?;
+        Ok(Self { cubes })
+    }
+}
+
+impl Display for Bag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cubes(f, &self.cubes)
+    }
+}
+
+/// Every game in the input, parsed once so they can be checked against any
+/// number of bags.
+#[derive(Debug)]
+pub struct Games {
+    games: Vec<Game>,
+}
+
+impl Display for Games {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for game in self.games.iter() {
+            writeln!(f, "{game}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Games {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let games = s
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| parse_game(line).map_err(|e| format_err!("Line {}: {e}", idx + 1)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Games { games })
+    }
+}
+
+#[derive(Error, Debug)]
+enum GameError {
+    #[error("More than one collection with the {0} color in a game")]
+    ColorRepeated(String),
+    #[error("Unexpected input at column {column}: {rest:?}")]
+    Syntax { column: usize, rest: String },
+}
+
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// The counts and colors of one round, in the order they were written.
+type Cubes<'a> = Vec<(usize, &'a str)>;
+
+fn number(input: &str) -> ParseResult<'_, usize> {
+    map_res(digit1, str::parse)(input)
+}
+
+fn color(input: &str) -> ParseResult<'_, &str> {
+    take_while1(char::is_alphabetic)(input)
+}
+
+/// `3 blue`, or `blue 3`.
+fn cubes(input: &str) -> ParseResult<'_, (usize, &str)> {
+    alt((
+        separated_pair(number, multispace1, color),
+        map(
+            separated_pair(color, multispace1, number),
+            |(color, num)| (num, color),
+        ),
+    ))(input)
+}
+
+fn separator<'a>(c: char) -> impl FnMut(&'a str) -> ParseResult<'a, char> {
+    delimited(multispace0, char(c), multispace0)
+}
+
+fn round(input: &str) -> ParseResult<'_, Cubes<'_>> {
+    separated_list1(separator(','), cubes)(input)
+}
+
+/// `Game 1: 3 blue, 4 red; 1 red`, with any amount of whitespace around the
+/// words and separators.
+fn game(input: &str) -> ParseResult<'_, (usize, Vec<Cubes<'_>>)> {
+    let (i, _) = delimited(multispace0, tag("Game"), multispace1)(input)?;
+    let (i, number) = number(i)?;
+    let (i, _) = separator(':')(i)?;
+    let (i, rounds) = separated_list1(separator(';'), round)(i)?;
+    let (i, _) = multispace0(i)?;
+    Ok((i, (number, rounds)))
+}
+
+/// Turns a parse of all of `input` into a result, pointing at where it went
+/// wrong if it didn't work.
+fn finish<'a, T>(input: &str, res: ParseResult<'a, T>) -> Result<T, GameError> {
+    match res.finish() {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(e) => {
+            let rest = e.errors.first().map_or("", |(rest, _)| *rest);
+            Err(GameError::Syntax {
+                column: input.len() - rest.len() + 1,
+                rest: rest.to_owned(),
+            })
+        }
+    }
+}
+
+impl GameRound {
+    fn from_cubes(found: Cubes) -> Result<Self, GameError> {
+        let mut cubes = BTreeMap::new();
+        for (num, color) in found {
+            if cubes.insert(color.to_owned(), num).is_some() {
+                return Err(GameError::ColorRepeated(color.to_owned()));
+            }
+        }
+        Ok(GameRound { cubes })
+    }
+}
+
+fn parse_game(line: &str) -> Result<Game, GameError> {
+    let (number, rounds) = finish(line, all_consuming(game)(line))?;
+    let rounds = rounds
+        .into_iter()
+        .map(GameRound::from_cubes)
+        .collect::<Result<Vec<_>, GameError>>()?;
+    Ok(Game { number, rounds })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    const EXAMPLE_GAMES: &'static str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+
+    #[test]
+    fn from_problem() {
+        let games = EXAMPLE_GAMES.parse().unwrap();
+        assert_eq!(part1(&games, &Bag::default()).unwrap(), 8);
+    }
+
+    #[test]
+    fn from_problem_p2() {
+        assert_eq!(
+            part2(&EXAMPLE_GAMES.parse().unwrap(), &Bag::default()).unwrap(),
+            2286
+        );
+    }
+
+    #[test]
+    fn any_colors() {
+        let games = "Game 1: 2 yellow, 3 purple; 5 yellow
+Game 2: 1 purple, 2 red, 4 yellow; 3 red"
+            .parse()
+            .unwrap();
+        let bag: Bag = "9 yellow, 9 purple".parse().unwrap();
+        assert_eq!(part2(&games, &bag).unwrap(), 5 * 3 + 4);
+        let bag: Bag = "9 yellow, 9 purple, 9 red".parse().unwrap();
+        assert_eq!(part2(&games, &bag).unwrap(), 4 * 3);
+        assert_eq!(part1(&games, &Bag::default()).unwrap(), 0);
+
+        let game = parse_game("Game 7: 1 teal; 4 teal, 2 ochre").unwrap();
+        let min_set = game.min_set();
+        assert_eq!(Some(&4), min_set.cubes.get("teal"));
+        let bag: Bag = "5 teal, 5 ochre".parse().unwrap();
+        assert_eq!(8, min_set.power(&bag));
+
+        // A puzzle color that never shows makes the power 0.
+        let game = parse_game("Game 8: 3 red; 2 green, 1 red").unwrap();
+        let min_set = game.min_set();
+        assert_eq!(None, min_set.cubes.get("blue"));
+        assert_eq!(0, min_set.power(&Bag::default()));
+
+        assert!(GameRound::from_str("1 red, 2 red").is_err());
+        assert!(GameRound::from_str("3").is_err());
+        assert!(GameRound::from_str("3 blue!").is_err());
+    }
+
+    #[test]
+    fn bags() {
+        let games: Games = EXAMPLE_GAMES.parse().unwrap();
+        let sums: Vec<usize> = [
+            "12 red, 13 green, 14 blue",
+            "20 red, 13 green, 15 blue",
+            "1 red",
+        ]
+        .iter()
+        .map(|bag| part1(&games, &bag.parse().unwrap()).unwrap())
+        .collect();
+        assert_eq!(vec![8, 15, 0], sums);
+
+        let bag: Bag = "14 blue, 12 red, 13 green".parse().unwrap();
+        assert_eq!(Bag::default(), bag);
+        assert_eq!("14 blue, 13 green, 12 red", bag.to_string());
+        assert!("12 red, 3 red".parse::<Bag>().is_err());
+    }
+
+    #[test]
+    fn impossible_games() {
+        let games = EXAMPLE_GAMES.parse().unwrap();
+        let report = part1_report(&games, &Bag::default());
+        assert_eq!(8, report.sum);
+        assert_eq!(
+            vec![
+                "Game 3 round 1: 20 red shown, but the bag only has 12",
+                "Game 4 round 3: 15 blue shown, but the bag only has 14",
+            ],
+            report
+                .impossible
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+        );
+
+        let corrupted = "Game 1: 3 blue, 4 red\nGame 2: 3 blue, 4 rde!\n";
+        let err = corrupted.parse::<Games>().unwrap_err();
+        assert_eq!(
+            "Line 2: Unexpected input at column 22: \"!\"",
+            err.to_string()
+        );
+        assert!("Game 1: 1 red\nGame two: 1 red".parse::<Games>().is_err());
+        assert!("Gaem 1: 1 red".parse::<Games>().is_err());
+    }
+
+    #[test]
+    fn flexible_whitespace() {
+        let game = parse_game("  Game 3 :3 blue,red 4 ;\t1 red,  2   green  \r").unwrap();
+        assert_eq!("Game 3: 3 blue, 4 red; 2 green, 1 red", game.to_string());
+        assert_eq!(game, parse_game(&game.to_string()).unwrap());
+        assert!(parse_game("Game 3: 3 blue,, 4 red").is_err());
+        assert!(parse_game("Game 3: 3 blue; ").is_err());
+        assert!(parse_game("Game3: 3 blue").is_err());
+    }
+
+    fn arb_game() -> impl Strategy<Value = Game> {
+        let round = prop::collection::btree_map("[a-z]{1,8}", 0..100usize, 1..5)
+            .prop_map(|cubes| GameRound { cubes });
+        (0..1000usize, prop::collection::vec(round, 1..6))
+            .prop_map(|(number, rounds)| Game { number, rounds })
+    }
+
+    proptest! {
+        #[test]
+        fn display_roundtrip(game in arb_game()) {
+            prop_assert_eq!(&game, &parse_game(&game.to_string()).unwrap());
+        }
+
+        #[test]
+        fn whitespace_doesnt_matter(game in arb_game(), ws in "[ \t]{0,3}") {
+            let spaced = game
+                .to_string()
+                .replace(", ", &format!("{ws},{ws}"))
+                .replace("; ", &format!("{ws};{ws} "))
+                .replace(": ", &format!("{ws}:{ws}"));
+            prop_assert_eq!(&game, &parse_game(&format!("{ws}{spaced}{ws}")).unwrap());
+        }
+    }
+}
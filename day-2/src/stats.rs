@@ -0,0 +1,325 @@
+//! How likely games are with a given bag, which bag most likely produced a
+//! game log, and made up game logs drawn from a bag.
+//!
+//! Each round is taken to be a handful of cubes grabbed at random, as many as
+//! the round shows.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{format_err, Error};
+use rand::Rng;
+
+use crate::{Bag, Game, GameRound, Games};
+
+/// What happens to the cubes shown in a round.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Draw {
+    /// They go back in the bag before the next round.
+    WithReplacement,
+    /// They stay out of the bag until the game is over.
+    WithoutReplacement,
+}
+
+impl FromStr for Draw {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "with" => Ok(Self::WithReplacement),
+            "without" => Ok(Self::WithoutReplacement),
+            _ => Err(format_err!("Expected with or without: {s}")),
+        }
+    }
+}
+
+/// ln(n choose k), which is -inf when there's no way to choose.
+fn ln_choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (0..k)
+        .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+        .sum()
+}
+
+/// ln of the chance that a handful the size of `round` drawn from `bag` is
+/// exactly the cubes of `round`.
+fn round_ln_p(bag: &BTreeMap<String, usize>, round: &GameRound) -> f64 {
+    let ways: f64 = round
+        .cubes
+        .iter()
+        .map(|(color, num)| ln_choose(bag.get(color).copied().unwrap_or(0), *num))
+        .sum();
+    if ways == f64::NEG_INFINITY {
+        return ways;
+    }
+    ways - ln_choose(bag.values().sum(), round.cubes.values().sum())
+}
+
+impl Bag {
+    /// ln of the chance of seeing the rounds of `game`, in order, when drawn
+    /// from this bag. Impossible games get -inf.
+    pub fn ln_likelihood(&self, game: &Game, draw: Draw) -> f64 {
+        let mut bag = self.cubes.clone();
+        let mut ln_p = 0.0;
+        for round in game.rounds.iter() {
+            ln_p += round_ln_p(&bag, round);
+            if ln_p == f64::NEG_INFINITY {
+                break;
+            }
+            if draw == Draw::WithoutReplacement {
+                for (color, num) in round.cubes.iter() {
+                    if let Some(left) = bag.get_mut(color) {
+                        *left -= num;
+                    }
+                }
+            }
+        }
+        ln_p
+    }
+
+    /// The chance of seeing the rounds of `game`, in order.
+    pub fn probability(&self, game: &Game, draw: Draw) -> f64 {
+        self.ln_likelihood(game, draw).exp()
+    }
+
+    /// Plays `sim.games` made up games with this bag.
+    pub fn simulate(&self, sim: &Simulation, rng: &mut impl Rng) -> Games {
+        let games = (1..=sim.games)
+            .map(|number| {
+                let mut bag = self.cubes.clone();
+                let rounds = (0..rng.gen_range(1..=sim.rounds.max(1)))
+                    .map_while(|_| {
+                        let left: usize = bag.values().sum();
+                        if left == 0 {
+                            return None;
+                        }
+                        let round =
+                            grab(&mut bag, rng.gen_range(1..=sim.handful.clamp(1, left)), rng);
+                        if sim.draw == Draw::WithReplacement {
+                            for (color, num) in round.cubes.iter() {
+                                *bag.get_mut(color).unwrap() += num;
+                            }
+                        }
+                        Some(round)
+                    })
+                    .collect();
+                Game { number, rounds }
+            })
+            .collect();
+        Games { games }
+    }
+}
+
+/// Takes `size` cubes out of `bag` at random.
+fn grab(bag: &mut BTreeMap<String, usize>, size: usize, rng: &mut impl Rng) -> GameRound {
+    let mut cubes = BTreeMap::new();
+    for _ in 0..size {
+        let mut pick = rng.gen_range(0..bag.values().sum::<usize>());
+        for (color, left) in bag.iter_mut() {
+            if pick < *left {
+                *left -= 1;
+                *cubes.entry(color.clone()).or_insert(0) += 1;
+                break;
+            }
+            pick -= *left;
+        }
+    }
+    GameRound { cubes }
+}
+
+/// How to make up a game log: each game has 1 to `rounds` rounds, each
+/// showing a handful of 1 to `handful` cubes.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub games: usize,
+    pub rounds: usize,
+    pub handful: usize,
+    pub draw: Draw,
+}
+
+impl Games {
+    pub fn ln_likelihood(&self, bag: &Bag, draw: Draw) -> f64 {
+        self.games.iter().map(|g| bag.ln_likelihood(g, draw)).sum()
+    }
+
+    /// The fewest cubes of each color that make every game possible.
+    fn smallest_bag(&self, draw: Draw) -> BTreeMap<String, usize> {
+        let mut cubes = BTreeMap::new();
+        for game in self.games.iter() {
+            let needed = match draw {
+                Draw::WithReplacement => game.min_set().cubes,
+                Draw::WithoutReplacement => {
+                    let mut shown = BTreeMap::new();
+                    for (color, num) in game.rounds.iter().flat_map(|r| r.cubes.iter()) {
+                        *shown.entry(color.clone()).or_insert(0) += num;
+                    }
+                    shown
+                }
+            };
+            for (color, num) in needed {
+                let max = cubes.entry(color).or_insert(0);
+                *max = (*max).max(num);
+            }
+        }
+        cubes
+    }
+
+    /// The maximum likelihood estimate of the bag these games were played
+    /// with, with at most `max` cubes of a color unless the games need more.
+    /// Only colors that were seen can be estimated. An estimate that reaches
+    /// `max` means bigger bags with the same mix fit the games at least as
+    /// well.
+    pub fn estimate_bag(&self, draw: Draw, max: usize) -> Bag {
+        let smallest = self.smallest_bag(draw);
+        let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+        for (color, num) in self
+            .games
+            .iter()
+            .flat_map(|g| g.rounds.iter().flat_map(|r| r.cubes.iter()))
+        {
+            *seen.entry(color).or_insert(0) += num;
+        }
+        let seen_total = seen.values().sum::<usize>().max(1) as f64;
+
+        // Scaling every color up together barely changes the likelihood, so
+        // climbing one color at a time gets stuck well short of the peak.
+        // Start from the best bag of any size with the colors in the
+        // proportions they were seen.
+        let limit = smallest.values().copied().max().unwrap_or(0).max(max);
+        let (mut bag, mut best) = (0..=limit * smallest.len())
+            .map(|total| {
+                let cubes = smallest
+                    .iter()
+                    .map(|(color, least)| {
+                        let share = seen[color.as_str()] as f64 / seen_total;
+                        let num = (share * total as f64).round() as usize;
+                        (color.clone(), num.clamp(*least, max.max(*least)))
+                    })
+                    .collect();
+                let bag = Bag { cubes };
+                let ln_l = self.ln_likelihood(&bag, draw);
+                (bag, ln_l)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("there is always an empty bag to try");
+
+        // Then polish one cube at a time until no single change helps.
+        loop {
+            let mut improved = false;
+            for (color, least) in smallest.iter() {
+                for step in [1, -1] {
+                    let num = bag.cubes[color].saturating_add_signed(step);
+                    if num < *least || num > max.max(*least) {
+                        continue;
+                    }
+                    let mut tried = bag.clone();
+                    tried.cubes.insert(color.clone(), num);
+                    let ln_l = self.ln_likelihood(&tried, draw);
+                    if ln_l > best + 1e-9 {
+                        (bag, best, improved) = (tried, ln_l, true);
+                    }
+                }
+            }
+            if !improved {
+                return bag;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn game(s: &str) -> Game {
+        crate::parse_game(s).unwrap()
+    }
+
+    #[test]
+    fn choosing() {
+        assert_eq!(1.0, ln_choose(5, 0).exp());
+        assert!((ln_choose(5, 2).exp() - 10.0).abs() < 1e-9);
+        assert!((ln_choose(20, 13).exp() - 77520.0).abs() < 1e-6);
+        assert_eq!(f64::NEG_INFINITY, ln_choose(2, 3));
+    }
+
+    #[test]
+    fn probabilities() {
+        use Draw::*;
+
+        let bag: Bag = "1 red, 1 blue".parse().unwrap();
+        let p = |s: &str, draw| bag.probability(&game(s), draw);
+        assert!((p("Game 1: 1 red", WithReplacement) - 0.5).abs() < 1e-9);
+        assert!((p("Game 1: 1 red; 1 red", WithReplacement) - 0.25).abs() < 1e-9);
+        assert_eq!(0.0, p("Game 1: 1 red; 1 red", WithoutReplacement));
+        assert!((p("Game 1: 1 red; 1 blue", WithoutReplacement) - 0.5).abs() < 1e-9);
+        assert!((p("Game 1: 1 blue, 1 red", WithoutReplacement) - 1.0).abs() < 1e-9);
+        assert_eq!(0.0, p("Game 1: 1 green", WithReplacement));
+
+        // Every handful of two from 2 red and 2 blue.
+        let bag: Bag = "2 red, 2 blue".parse().unwrap();
+        let total: f64 = ["2 red", "1 red, 1 blue", "2 blue"]
+            .iter()
+            .map(|r| bag.probability(&game(&format!("Game 1: {r}")), WithReplacement))
+            .sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn estimates() {
+        let games: Games = "Game 1: 3 red, 2 blue".parse().unwrap();
+        let bag = games.estimate_bag(Draw::WithReplacement, 100);
+        assert_eq!("2 blue, 3 red", bag.to_string());
+
+        let games: Games = "Game 1: 1 red; 1 red, 1 blue\nGame 2: 2 red, 2 blue; 1 blue"
+            .parse()
+            .unwrap();
+        let bag = games.estimate_bag(Draw::WithoutReplacement, 50);
+        // Going without replacement, game 2 needs at least 3 blue.
+        assert!(bag.cubes["blue"] >= 3 && bag.cubes["red"] >= 2);
+        assert!(bag.cubes.values().all(|num| *num <= 50));
+        let best = games.ln_likelihood(&bag, Draw::WithoutReplacement);
+        for (color, num) in bag.cubes.iter() {
+            for other in [num - 1, num + 1] {
+                let mut other_bag = bag.clone();
+                other_bag.cubes.insert(color.clone(), other);
+                assert!(games.ln_likelihood(&other_bag, Draw::WithoutReplacement) <= best);
+            }
+        }
+    }
+
+    #[test]
+    fn simulated() {
+        let bag = Bag::default();
+        let mut rng = StdRng::seed_from_u64(2);
+        for draw in [Draw::WithReplacement, Draw::WithoutReplacement] {
+            let sim = Simulation {
+                games: 400,
+                rounds: 6,
+                handful: 15,
+                draw,
+            };
+            let games = bag.simulate(&sim, &mut rng);
+            assert_eq!(400, games.games.len());
+            for game in games.games.iter() {
+                assert!((1..=6).contains(&game.rounds.len()));
+                assert!(bag.probability(game, draw) > 0.0);
+            }
+
+            // Plenty of games should lead back to roughly the bag they came from.
+            let estimate = games.estimate_bag(draw, 100);
+            for (color, num) in bag.cubes.iter() {
+                let estimated = estimate.cubes[color] as f64;
+                assert!(
+                    (estimated - *num as f64).abs() <= 3.0,
+                    "{draw:?}: estimated {estimate} for {bag}"
+                );
+            }
+        }
+    }
+}
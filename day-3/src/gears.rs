@@ -0,0 +1,191 @@
+//! Gears under rules other than the puzzle's: which symbols can be gears, how
+//! many numbers they need and how those numbers make the ratio.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{format_err, Error};
+
+use crate::Board;
+
+/// How many part numbers a gear needs to touch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn allows(&self, count: usize) -> bool {
+        match *self {
+            Self::Exactly(n) => count == n,
+            Self::AtLeast(n) => count >= n,
+        }
+    }
+}
+
+impl FromStr for Arity {
+    type Err = Error;
+
+    /// "2" is exactly two, "2+" is two or more.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = |e| format_err!("Expected a count like 2 or 2+: {s} ({e})");
+        match s.strip_suffix('+') {
+            Some(n) => Ok(Self::AtLeast(n.parse().map_err(bad)?)),
+            None => Ok(Self::Exactly(s.parse().map_err(bad)?)),
+        }
+    }
+}
+
+/// How the part numbers around a gear make its ratio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Combine {
+    pub fn apply(&self, numbers: &[usize]) -> usize {
+        match self {
+            Self::Product => numbers.iter().product(),
+            Self::Sum => numbers.iter().sum(),
+            Self::Max => numbers.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+impl FromStr for Combine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Self::Product),
+            "sum" => Ok(Self::Sum),
+            "max" => Ok(Self::Max),
+            _ => Err(format_err!("Expected product, sum or max: {s}")),
+        }
+    }
+}
+
+/// What makes a symbol a gear. The default is the puzzle's: a `*` touching
+/// exactly two part numbers, multiplied together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub arity: Arity,
+    pub combine: Combine,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
+        Self {
+            symbols: vec!['*'],
+            arity: Arity::Exactly(2),
+            combine: Combine::Product,
+        }
+    }
+}
+
+/// A symbol that passed a [`GearRule`], with the part numbers around it in
+/// reading order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gear {
+    pub symbol: char,
+    /// `(row, col)` of the symbol.
+    pub loc: (usize, usize),
+    pub numbers: Vec<usize>,
+    pub ratio: usize,
+}
+
+impl fmt::Display for Gear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (row, col) = self.loc;
+        write!(f, "{} at {row}, {col} with ", self.symbol)?;
+        for (i, num) in self.numbers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{num}")?;
+        }
+        write!(f, ": {}", self.ratio)
+    }
+}
+
+impl Board {
+    /// Every gear under `rule`, in reading order.
+    pub fn find_gears(&self, rule: &GearRule) -> Vec<Gear> {
+        self.adjacency()
+            .into_iter()
+            .filter(|(_, s)| rule.symbols.contains(&s.symbol) && rule.arity.allows(s.numbers.len()))
+            .map(|(loc, s)| Gear {
+                symbol: s.symbol,
+                loc,
+                ratio: rule.combine.apply(&s.numbers),
+                numbers: s.numbers,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const SCHEM: &str = "\
+10*20*30
+..#...+.
+.5......";
+
+    #[test]
+    fn rules() {
+        let board: Board = SCHEM.parse().unwrap();
+        let ratios = |rule: &GearRule| -> Vec<usize> {
+            board
+                .find_gears(rule)
+                .into_iter()
+                .map(|g| g.ratio)
+                .collect()
+        };
+        assert_eq!(vec![200, 600], ratios(&GearRule::default()));
+
+        let rule = GearRule {
+            symbols: vec!['*', '#', '+'],
+            arity: Arity::AtLeast(2),
+            combine: Combine::Sum,
+        };
+        assert_eq!(vec![30, 50, 35], ratios(&rule));
+
+        let rule = GearRule {
+            symbols: vec!['#', '+'],
+            arity: Arity::Exactly(1),
+            combine: Combine::Max,
+        };
+        assert_eq!(vec![30], ratios(&rule));
+
+        let gears = board.find_gears(&GearRule {
+            arity: "3+".parse().unwrap(),
+            ..rule
+        });
+        assert_eq!(
+            vec![Gear {
+                symbol: '#',
+                loc: (1, 2),
+                numbers: vec![10, 20, 5],
+                ratio: 20,
+            }],
+            gears
+        );
+        assert_eq!("# at 1, 2 with 10, 20, 5: 20", gears[0].to_string());
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(Arity::Exactly(2), "2".parse().unwrap());
+        assert_eq!(Arity::AtLeast(3), "3+".parse().unwrap());
+        assert!("two".parse::<Arity>().is_err());
+        assert_eq!(Combine::Max, "max".parse().unwrap());
+        assert!("min".parse::<Combine>().is_err());
+    }
+}
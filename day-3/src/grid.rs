@@ -0,0 +1,248 @@
+use std::ops::{Index, IndexMut, Range};
+
+use anyhow::{format_err, Error};
+
+/// A rectangle of cells stored row by row, addressed by `(row, col)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// Offsets to the cells sharing an edge with a cell.
+const EDGES: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets to the cells sharing an edge or a corner with a cell.
+const AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from rows that must all be the same length.
+    pub fn from_rows<R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Self, Error> {
+        let mut cells = Vec::new();
+        let (mut width, mut height) = (0, 0);
+        for row in rows {
+            cells.extend(row);
+            if height == 0 {
+                width = cells.len();
+            }
+            height += 1;
+            if cells.len() != width * height {
+                return Err(format_err!(
+                    "Row {height} is {} long, expected {width}",
+                    cells.len() - width * (height - 1)
+                ));
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Builds a grid from rows of any length, padding the short ones out to
+    /// the longest with `fill`.
+    pub fn from_ragged_rows<R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+        fill: T,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let rows: Vec<Vec<T>> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in rows.iter() {
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + width - row.len(), fill.clone());
+        }
+        Self {
+            width,
+            height: rows.len(),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(col < self.width, "column {col} out of {}", self.width);
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(idx, c)| ((idx / width, idx % width), c))
+    }
+
+    fn offsets<'a>(
+        &self,
+        (row, col): (usize, usize),
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |(dr, dc)| {
+            let row = row.checked_add_signed(*dr).filter(|r| *r < height)?;
+            let col = col.checked_add_signed(*dc).filter(|c| *c < width)?;
+            Some((row, col))
+        })
+    }
+
+    /// The positions of the up to 4 cells sharing an edge with `pos`.
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(pos, &EDGES)
+    }
+
+    /// The positions of the up to 8 cells sharing an edge or a corner with
+    /// `pos`.
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(pos, &AROUND)
+    }
+
+    /// The positions in `rows` x `cols`, row by row, leaving out any that are
+    /// off the grid. The ranges may start before 0 or end past the edge.
+    pub fn region(
+        &self,
+        rows: Range<isize>,
+        cols: Range<isize>,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let clip = |r: Range<isize>, len: usize| {
+            let len = len as isize;
+            r.start.clamp(0, len) as usize..r.end.clamp(0, len) as usize
+        };
+        let (rows, cols) = (clip(rows, self.height), clip(cols, self.width));
+        rows.flat_map(move |row| cols.clone().map(move |col| (row, col)))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is off the {}x{} grid", self.height, self.width))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        let (height, width) = (self.height, self.width);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is off the {height}x{width} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn grid() -> Grid<char> {
+        Grid::from_rows(["abc", "def", "ghi", "jkl"].map(str::chars)).unwrap()
+    }
+
+    #[test]
+    fn access() {
+        let mut g = grid();
+        assert_eq!((3, 4), (g.width(), g.height()));
+        assert_eq!('f', g[(1, 2)]);
+        assert_eq!(None, g.get((1, 3)));
+        assert_eq!(None, g.get((4, 0)));
+        g[(3, 0)] = 'J';
+        assert_eq!(&['J', 'k', 'l'], g.row(3));
+        assert_eq!("behk", g.column(1).collect::<String>());
+        assert_eq!("lifc", g.column(2).rev().collect::<String>());
+        assert_eq!(4, g.rows().count());
+        assert_eq!(((2, 1), &'h'), g.cells().nth(7).unwrap());
+
+        assert!(Grid::from_rows(["ab", "abc"].map(str::chars)).is_err());
+        let ragged = Grid::from_ragged_rows(["ab", "", "abc"].map(str::chars), '.');
+        assert_eq!((3, 3), (ragged.width(), ragged.height()));
+        assert_eq!(
+            "ab....abc",
+            ragged.cells().map(|(_, c)| c).collect::<String>()
+        );
+        let empty: Grid<char> = Grid::from_rows(Vec::<Vec<char>>::new()).unwrap();
+        assert_eq!(0, empty.rows().count());
+        let blank: Grid<char> = Grid::from_rows(["", ""].map(str::chars)).unwrap();
+        assert_eq!(vec![0, 0], blank.rows().map(<[_]>::len).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn neighbors() {
+        let g = grid();
+        let at = |pos, four: bool| -> String {
+            if four {
+                g.neighbors4(pos).map(|p| g[p]).collect()
+            } else {
+                g.neighbors8(pos).map(|p| g[p]).collect()
+            }
+        };
+        assert_eq!("bd", at((0, 0), true));
+        assert_eq!("bde", at((0, 0), false));
+        assert_eq!("bdfh", at((1, 1), true));
+        assert_eq!("abcdfghi", at((1, 1), false));
+        assert_eq!("ghijl", at((3, 1), false));
+    }
+
+    #[test]
+    fn regions() {
+        let g = grid();
+        let region = |rows, cols| -> String { g.region(rows, cols).map(|p| g[p]).collect() };
+        assert_eq!("abde", region(-1..2, -1..2));
+        assert_eq!("hikl", region(2..10, 1..3));
+        assert_eq!("", region(-3..-1, 0..3));
+        assert_eq!("", region(1..1, 0..3));
+        assert_eq!("abcdefghijkl", region(-5..50, -5..50));
+    }
+}
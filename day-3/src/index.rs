@@ -0,0 +1,371 @@
+//! A schematic that can be edited a cell at a time, keeping the part number
+//! and gear ratio sums up to date by only looking around each edit.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use anyhow::{format_err, Error};
+
+use crate::{is_symbol, parse_grid, GearRule, Grid};
+
+/// A number on the schematic, keyed by where it starts.
+#[derive(Debug, Clone, PartialEq)]
+struct Number {
+    /// The column of the last digit.
+    end: usize,
+    value: usize,
+    /// Whether it touches a symbol and so counts towards the part sum.
+    counted: bool,
+}
+
+/// A change made to one cell, as kept in the undo log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edit {
+    pub pos: (usize, usize),
+    pub old: char,
+    pub new: char,
+}
+
+/// An editable schematic with its part number and gear ratio sums.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    grid: Grid<char>,
+    rule: GearRule,
+    numbers: BTreeMap<(usize, usize), Number>,
+    /// The start of the number each digit belongs to.
+    owners: Grid<Option<(usize, usize)>>,
+    /// The ratio of every symbol that's a gear under `rule`.
+    gears: BTreeMap<(usize, usize), usize>,
+    part_sum: usize,
+    gear_sum: usize,
+    undo: Vec<Edit>,
+}
+
+impl Schematic {
+    pub fn new(grid: Grid<char>, rule: GearRule) -> Self {
+        let owners = Grid::new(grid.width(), grid.height(), None);
+        let mut schematic = Self {
+            grid,
+            rule,
+            numbers: BTreeMap::new(),
+            owners,
+            gears: BTreeMap::new(),
+            part_sum: 0,
+            gear_sum: 0,
+            undo: Vec::new(),
+        };
+        for row in 0..schematic.grid.height() {
+            let mut col = 0;
+            while col < schematic.grid.width() {
+                if schematic.grid[(row, col)].is_ascii_digit() {
+                    col = schematic.add_number(row, col);
+                }
+                col += 1;
+            }
+        }
+        let symbols: Vec<_> = schematic
+            .grid
+            .cells()
+            .filter(|(_, c)| is_symbol(**c))
+            .map(|(pos, _)| pos)
+            .collect();
+        for pos in symbols {
+            schematic.update_gear(pos);
+        }
+        schematic
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    /// The sum of the numbers touching a symbol.
+    pub fn part_sum(&self) -> usize {
+        self.part_sum
+    }
+
+    /// The sum of the ratios of the gears.
+    pub fn gear_sum(&self) -> usize {
+        self.gear_sum
+    }
+
+    /// The edits that can be undone, oldest first.
+    pub fn history(&self) -> &[Edit] {
+        &self.undo
+    }
+
+    /// Writes `c` to the cell at `pos`.
+    pub fn set(&mut self, pos: (usize, usize), c: char) -> Result<(), Error> {
+        let old = *self.grid.get(pos).ok_or_else(|| {
+            format_err!(
+                "{pos:?} is off the {}x{} schematic",
+                self.grid.height(),
+                self.grid.width()
+            )
+        })?;
+        self.apply(pos, c);
+        self.undo.push(Edit { pos, old, new: c });
+        Ok(())
+    }
+
+    /// Blanks the cell at `pos` with a `.`.
+    pub fn clear(&mut self, pos: (usize, usize)) -> Result<(), Error> {
+        self.set(pos, '.')
+    }
+
+    /// Takes back the last edit, if there is one.
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.undo.pop()?;
+        self.apply(edit.pos, edit.old);
+        Some(edit)
+    }
+
+    fn apply(&mut self, pos: (usize, usize), c: char) {
+        let (row, col) = pos;
+        let cols = col.saturating_sub(1)..(col + 2).min(self.grid.width());
+
+        // The numbers in the row that could grow, shrink, split or join up
+        // are taken off and put back as they are after the edit. Every cell
+        // they covered either way is kept to find the gears they touch.
+        let mut touched = vec![pos];
+        for c in cols.clone() {
+            if let Some(start) = self.owners[(row, c)] {
+                let number = self.remove_number(start);
+                touched.extend((start.1..=number.end).map(|c| (row, c)));
+            }
+        }
+        self.grid[pos] = c;
+        for c in cols {
+            if self.grid[(row, c)].is_ascii_digit() && self.owners[(row, c)].is_none() {
+                let mut start = c;
+                while start > 0 && self.grid[(row, start - 1)].is_ascii_digit() {
+                    start -= 1;
+                }
+                let end = self.add_number(row, start);
+                touched.extend((start..=end).map(|c| (row, c)));
+            }
+        }
+
+        // The cell may have stopped or started being a symbol.
+        let around: BTreeSet<_> = self
+            .grid
+            .neighbors8(pos)
+            .filter_map(|p| self.owners[p])
+            .collect();
+        for start in around {
+            self.update_counted(start);
+        }
+
+        let symbols: BTreeSet<_> = touched
+            .iter()
+            .flat_map(|p| self.grid.neighbors8(*p).chain([*p]))
+            .collect();
+        for pos in symbols {
+            self.update_gear(pos);
+        }
+    }
+
+    /// Indexes the number starting at `(row, start)`, returning the column of
+    /// its last digit.
+    fn add_number(&mut self, row: usize, start: usize) -> usize {
+        let digits: String = self.grid.row(row)[start..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        let end = start + digits.len() - 1;
+        for col in start..=end {
+            self.owners[(row, col)] = Some((row, start));
+        }
+        let number = Number {
+            end,
+            value: digits.parse().unwrap(),
+            counted: self.touches_symbol(row, start, end),
+        };
+        if number.counted {
+            self.part_sum += number.value;
+        }
+        self.numbers.insert((row, start), number);
+        end
+    }
+
+    fn remove_number(&mut self, start: (usize, usize)) -> Number {
+        let number = self
+            .numbers
+            .remove(&start)
+            .expect("owners point at numbers");
+        for col in start.1..=number.end {
+            self.owners[(start.0, col)] = None;
+        }
+        if number.counted {
+            self.part_sum -= number.value;
+        }
+        number
+    }
+
+    fn touches_symbol(&self, row: usize, start: usize, end: usize) -> bool {
+        let (r, s, e) = (row as isize, start as isize, end as isize);
+        self.grid
+            .region(r - 1..r + 2, s - 1..e + 2)
+            .any(|p| is_symbol(self.grid[p]))
+    }
+
+    fn update_counted(&mut self, start: (usize, usize)) {
+        let end = self.numbers[&start].end;
+        let counted = self.touches_symbol(start.0, start.1, end);
+        let number = self.numbers.get_mut(&start).unwrap();
+        if counted != number.counted {
+            number.counted = counted;
+            if counted {
+                self.part_sum += number.value;
+            } else {
+                self.part_sum -= number.value;
+            }
+        }
+    }
+
+    fn update_gear(&mut self, pos: (usize, usize)) {
+        if let Some(ratio) = self.gears.remove(&pos) {
+            self.gear_sum -= ratio;
+        }
+        let c = self.grid[pos];
+        if !is_symbol(c) || !self.rule.symbols.contains(&c) {
+            return;
+        }
+        let mut starts = Vec::new();
+        for start in self.grid.neighbors8(pos).filter_map(|p| self.owners[p]) {
+            if !starts.contains(&start) {
+                starts.push(start);
+            }
+        }
+        if self.rule.arity.allows(starts.len()) {
+            let values: Vec<_> = starts.iter().map(|s| self.numbers[s].value).collect();
+            let ratio = self.rule.combine.apply(&values);
+            self.gears.insert(pos, ratio);
+            self.gear_sum += ratio;
+        }
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = Error;
+
+    /// Reads a schematic with the puzzle's gears.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(parse_grid(s), GearRule::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use crate::{Arity, Board, Combine};
+
+    const SCHEM: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    /// The sums worked out from scratch.
+    fn recompute(grid: &Grid<char>, rule: &GearRule) -> (usize, usize) {
+        let board = Board { grid: grid.clone() };
+        let parts = board
+            .find_numbers()
+            .into_iter()
+            .filter(|n| !n.parts.is_empty())
+            .map(|n| n.number)
+            .sum();
+        let gears = board.find_gears(rule).into_iter().map(|g| g.ratio).sum();
+        (parts, gears)
+    }
+
+    #[test]
+    fn edits() {
+        let mut s: Schematic = SCHEM.parse().unwrap();
+        assert_eq!((4361, 467835), (s.part_sum(), s.gear_sum()));
+
+        // 114 turns into a part number and a gear.
+        s.set((1, 6), '*').unwrap();
+        assert_eq!(
+            (4361 + 114, 467835 + 114 * 633),
+            (s.part_sum(), s.gear_sum())
+        );
+
+        // Splitting 467 leaves 4 and 7 on the first gear.
+        s.clear((0, 1)).unwrap();
+        assert_eq!(4361 + 114 - 467 + 7, s.part_sum());
+        assert_eq!(467835 + 114 * 633 - 467 * 35 + 7 * 35, s.gear_sum());
+
+        // Joining 617 and the gear to its right into one number.
+        s.set((4, 3), '0').unwrap();
+        assert_eq!(
+            recompute(s.grid(), &GearRule::default()),
+            (s.part_sum(), s.gear_sum())
+        );
+
+        assert!(s.set((10, 0), '1').is_err());
+        assert_eq!(3, s.history().len());
+        assert_eq!(
+            Some(Edit {
+                pos: (4, 3),
+                old: '*',
+                new: '0'
+            }),
+            s.undo()
+        );
+        s.undo();
+        s.undo();
+        assert_eq!(None, s.undo());
+        assert_eq!((4361, 467835), (s.part_sum(), s.gear_sum()));
+    }
+
+    fn cell() -> impl Strategy<Value = char> {
+        prop::sample::select(".....0123456789**#".chars().collect::<Vec<_>>())
+    }
+
+    fn rule() -> impl Strategy<Value = GearRule> {
+        prop_oneof![
+            Just(GearRule::default()),
+            Just(GearRule {
+                symbols: vec!['*', '#'],
+                arity: Arity::AtLeast(1),
+                combine: Combine::Sum,
+            }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn matches_recompute(
+            rows in prop::collection::vec(prop::collection::vec(cell(), 8), 1..7),
+            edits in prop::collection::vec((0..7usize, 0..8usize, cell()), 0..30),
+            rule in rule(),
+        ) {
+            let grid = Grid::from_rows(rows).unwrap();
+            let mut s = Schematic::new(grid.clone(), rule.clone());
+            let start = recompute(&grid, &rule);
+            prop_assert_eq!(start, (s.part_sum(), s.gear_sum()));
+
+            for (row, col, c) in edits.iter() {
+                s.set((row % grid.height(), *col), *c).unwrap();
+                prop_assert_eq!(recompute(s.grid(), &rule), (s.part_sum(), s.gear_sum()));
+            }
+            while s.undo().is_some() {
+                prop_assert_eq!(recompute(s.grid(), &rule), (s.part_sum(), s.gear_sum()));
+            }
+            prop_assert_eq!(&grid, s.grid());
+            prop_assert_eq!(start, (s.part_sum(), s.gear_sum()));
+        }
+    }
+}
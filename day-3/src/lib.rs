@@ -0,0 +1,294 @@
+#![feature(ascii_char)]
+
+use anyhow::Error;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::str::FromStr;
+
+mod gears;
+mod grid;
+mod index;
+mod render;
+mod runs;
+
+pub use gears::{Arity, Combine, Gear, GearRule};
+pub use grid::Grid;
+pub use index::{Edit, Schematic};
+pub use render::Format;
+pub use runs::{Runs, RunsExt, Span};
+
+pub type ParsedInput = Board;
+
+pub fn part1(input: &ParsedInput) -> Result<usize, Error> {
+    let nums = input.find_part_numbers();
+    Ok(nums.into_iter().map(|p| p.number).sum())
+}
+
+pub fn part2(input: &ParsedInput) -> Result<usize, Error> {
+    let gears = input.find_gear_ratios();
+    Ok(gears.into_iter().sum())
+}
+
+/// The engine schematic.
+pub struct Board {
+    grid: Grid<char>,
+}
+
+/// Reads the schematic's rows, which can end in `\n` or `\r\n`. Short rows
+/// are padded out with `.`, as if they had blank cells on the end.
+fn parse_grid(s: &str) -> Grid<char> {
+    Grid::from_ragged_rows(s.lines().map(str::chars), '.')
+}
+
+/// Anything but an ASCII digit or a `.` is a symbol, Unicode ones included.
+fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+impl FromStr for Board {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            grid: parse_grid(s),
+        })
+    }
+}
+
+/// A symbol touching a number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Part {
+    part_type: char,
+    part_loc: (usize, usize),
+}
+
+/// A number on the board, with every symbol touching it. It's a part number
+/// if there's at least one.
+#[derive(Debug, Clone, PartialEq)]
+struct PartNum {
+    number: usize,
+    row: usize,
+    /// The columns of the first and last digit.
+    span: (usize, usize),
+    parts: Vec<Part>,
+}
+
+/// A symbol and the numbers touching it.
+#[derive(Debug, Clone, PartialEq)]
+struct Symbol {
+    symbol: char,
+    numbers: Vec<usize>,
+}
+
+impl Board {
+    /// Every number on the board, whether or not it's a part number.
+    fn find_numbers(&self) -> Vec<PartNum> {
+        let mut numbers = Vec::new();
+        for (row, line) in self.grid.rows().enumerate() {
+            for (start, end, num) in Board::find_nums_in_line(line) {
+                let (r, s, e) = (row as isize, start as isize, end as isize);
+                numbers.push(PartNum {
+                    number: num,
+                    row,
+                    span: (start, end),
+                    parts: self.find_symbols_in_range(r - 1..r + 2, s - 1..e + 2),
+                });
+            }
+        }
+        numbers
+    }
+
+    fn find_part_numbers(&self) -> Vec<PartNum> {
+        let mut parts = self.find_numbers();
+        parts.retain(|p| !p.parts.is_empty());
+        parts
+    }
+
+    /// Every symbol touching a number, with all the numbers it touches.
+    fn adjacency(&self) -> BTreeMap<(usize, usize), Symbol> {
+        let mut map = BTreeMap::new();
+        for num in self.find_numbers() {
+            for part in num.parts {
+                map.entry(part.part_loc)
+                    .or_insert_with(|| Symbol {
+                        symbol: part.part_type,
+                        numbers: vec![],
+                    })
+                    .numbers
+                    .push(num.number);
+            }
+        }
+        map
+    }
+
+    fn find_gear_ratios(&self) -> Vec<usize> {
+        let gears = self.find_gears(&GearRule::default());
+        gears.into_iter().map(|g| g.ratio).collect()
+    }
+
+    /// Every symbol in the clipped region `rows` x `cols`, row by row.
+    fn find_symbols_in_range(&self, rows: Range<isize>, cols: Range<isize>) -> Vec<Part> {
+        self.grid
+            .region(rows, cols)
+            .filter(|pos| is_symbol(self.grid[*pos]))
+            .map(|pos| Part {
+                part_type: self.grid[pos],
+                part_loc: pos,
+            })
+            .collect()
+    }
+
+    fn find_nums_in_line(line: &[char]) -> Vec<(usize, usize, usize)> {
+        line.iter()
+            .runs_by(|c| c.is_ascii_digit())
+            .filter(|span| span.key)
+            .map(|span| {
+                let digits: String = line[span.start..span.end].iter().collect();
+                (span.start, span.end - 1, digits.parse().unwrap())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_nums_in_line() {
+        let line = |s: &str| s.chars().collect::<Vec<_>>();
+        let nums = Board::find_nums_in_line(&line("145"));
+        assert_eq!(nums.len(), 1);
+        assert_eq!(nums[0], (0, 2, 145));
+
+        let nums = Board::find_nums_in_line(&line("123...145"));
+        assert_eq!(nums.len(), 2);
+        assert_eq!(nums[0], (0, 2, 123));
+        assert_eq!(nums[1], (6, 8, 145));
+    }
+
+    static SCHEM: &str = r#"467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598.."#;
+
+    #[test]
+    fn example_1() {
+        let board: Board = SCHEM.parse().unwrap();
+
+        let numbers = board.find_part_numbers();
+        assert_eq!(8, numbers.len());
+
+        assert_eq!(4361usize, numbers.iter().map(|n| n.number).sum());
+    }
+
+    #[test]
+    fn example_2() {
+        let board: Board = SCHEM.parse().unwrap();
+
+        let gears = board.find_gear_ratios();
+        assert_eq!(2, gears.len());
+        assert_eq!(467835usize, gears.into_iter().sum());
+    }
+
+    #[test]
+    fn shared_numbers() {
+        let board: Board = "10*20*30\n..#.....".parse().unwrap();
+        let twenty = &board.find_part_numbers()[1];
+        assert_eq!(20, twenty.number);
+        assert_eq!(
+            vec![(0, 2), (0, 5), (1, 2)],
+            twenty.parts.iter().map(|p| p.part_loc).collect::<Vec<_>>()
+        );
+
+        let gears = board.find_gear_ratios();
+        assert_eq!(vec![200, 600], gears);
+
+        let symbols = board.adjacency();
+        assert_eq!(
+            Symbol {
+                symbol: '#',
+                numbers: vec![10, 20]
+            },
+            symbols[&(1, 2)]
+        );
+    }
+
+    #[test]
+    fn ragged_rows() {
+        // The 7 is past the end of the first row, and the 9 is under nothing.
+        let board: Board = "12*\n..3...7\n.\n9".parse().unwrap();
+        assert_eq!((7, 4), (board.grid.width(), board.grid.height()));
+        let numbers = board.find_numbers();
+        assert_eq!(
+            vec![(12, true), (3, true), (7, false), (9, false)],
+            numbers
+                .iter()
+                .map(|n| (n.number, !n.parts.is_empty()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(15, part1(&board).unwrap());
+        assert_eq!(36, part2(&board).unwrap());
+        assert_eq!(0, part1(&"\n\n".parse().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn unicode_symbols() {
+        // Each of these is one cell wide however many bytes it takes.
+        let input = "é12..34€\n..→..56.\n7✱8.....";
+        let board: Board = input.parse().unwrap();
+        let numbers = board.find_numbers();
+        assert_eq!(
+            vec![
+                (12, (0, 1)),
+                (34, (0, 5)),
+                (56, (1, 5)),
+                (7, (2, 0)),
+                (8, (2, 2))
+            ],
+            numbers
+                .iter()
+                .map(|n| (n.number, (n.row, n.span.0)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!['é', '→'],
+            numbers[0]
+                .parts
+                .iter()
+                .map(|p| p.part_type)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(12 + 34 + 56 + 7 + 8, part1(&board).unwrap());
+
+        // A gear only needs to be a symbol the rule names.
+        let rule = GearRule {
+            symbols: vec!['✱'],
+            ..GearRule::default()
+        };
+        let gears = board.find_gears(&rule);
+        assert_eq!(
+            vec![((2, 1), 56)],
+            gears.iter().map(|g| (g.loc, g.ratio)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn crlf() {
+        let crlf = SCHEM.replace('\n', "\r\n") + "\r\n";
+        let board: Board = crlf.parse().unwrap();
+        assert_eq!(10, board.grid.width());
+        assert!(board.grid.cells().all(|(_, c)| *c != '\r'));
+        assert_eq!(4361, part1(&board).unwrap());
+        assert_eq!(467835, part2(&board).unwrap());
+
+        let schematic: Schematic = crlf.parse().unwrap();
+        assert_eq!((4361, 467835), (schematic.part_sum(), schematic.gear_sum()));
+    }
+}
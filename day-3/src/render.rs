@@ -0,0 +1,201 @@
+//! Draws a schematic with the part numbers, the numbers left out and the
+//! gears picked out, for checking answers by eye.
+
+use std::{collections::BTreeMap, fmt::Write, str::FromStr};
+
+use anyhow::{format_err, Error};
+
+use crate::{is_symbol, Board, Gear, GearRule, Grid, RunsExt};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Colored with ANSI escapes, for a terminal.
+    Ansi,
+    /// A standalone HTML page.
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Self::Ansi),
+            "html" => Ok(Self::Html),
+            _ => Err(format_err!("Expected ansi or html: {s}")),
+        }
+    }
+}
+
+/// What a cell is drawn as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Blank,
+    /// A digit of a part number.
+    Counted,
+    /// A digit of a number touching no symbol.
+    Excluded,
+    Symbol,
+    Gear,
+}
+
+impl Mark {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Self::Blank => "\x1b[2m",
+            Self::Counted => "\x1b[1;32m",
+            Self::Excluded => "\x1b[31m",
+            Self::Symbol => "\x1b[36m",
+            Self::Gear => "\x1b[1;33m",
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Self::Blank => "blank",
+            Self::Counted => "counted",
+            Self::Excluded => "excluded",
+            Self::Symbol => "symbol",
+            Self::Gear => "gear",
+        }
+    }
+}
+
+const STYLE: &str = "\
+body { background: #0f0f23; color: #cccccc; }
+pre { font-family: monospace; }
+.blank { color: #555555; }
+.counted { color: #00cc00; font-weight: bold; }
+.excluded { color: #ff4444; }
+.symbol { color: #44cccc; }
+.gear { color: #ffff66; font-weight: bold; }
+.ratio { color: #ffff66; }";
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Board {
+    fn marks(&self, gears: &[Gear]) -> Grid<Mark> {
+        let mut marks = Grid::new(self.grid.width(), self.grid.height(), Mark::Blank);
+        for (pos, c) in self.grid.cells() {
+            if is_symbol(*c) {
+                marks[pos] = Mark::Symbol;
+            }
+        }
+        for num in self.find_numbers() {
+            let mark = if num.parts.is_empty() {
+                Mark::Excluded
+            } else {
+                Mark::Counted
+            };
+            for col in num.span.0..=num.span.1 {
+                marks[(num.row, col)] = mark;
+            }
+        }
+        for gear in gears {
+            marks[gear.loc] = Mark::Gear;
+        }
+        marks
+    }
+
+    /// Draws the board, with the gears under `rule` listed after their row.
+    pub fn render(&self, rule: &GearRule, format: Format) -> String {
+        let gears = self.find_gears(rule);
+        let marks = self.marks(&gears);
+        let mut by_row: BTreeMap<usize, Vec<&Gear>> = BTreeMap::new();
+        for gear in gears.iter() {
+            by_row.entry(gear.loc.0).or_default().push(gear);
+        }
+
+        let mut out = String::new();
+        if format == Format::Html {
+            out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+            let _ = writeln!(
+                out,
+                "<title>Engine schematic</title>\n<style>\n{STYLE}\n</style>"
+            );
+            out.push_str("</head>\n<body>\n<pre>\n");
+        }
+        for (row, line) in self.grid.rows().enumerate() {
+            for span in marks.row(row).iter().runs_by(|m| *m) {
+                let text: String = line[span.start..span.end].iter().collect();
+                let _ = match format {
+                    Format::Ansi => write!(out, "{}{text}\x1b[0m", span.key.ansi()),
+                    Format::Html => write!(
+                        out,
+                        "<span class=\"{}\">{}</span>",
+                        span.key.class(),
+                        escape(&text)
+                    ),
+                };
+            }
+            for (i, gear) in by_row.get(&row).into_iter().flatten().enumerate() {
+                let sep = if i == 0 { "   " } else { "; " };
+                let _ = match format {
+                    Format::Ansi => write!(out, "{sep}\x1b[33m{gear}\x1b[0m"),
+                    Format::Html => write!(
+                        out,
+                        "{sep}<span class=\"ratio\">{}</span>",
+                        escape(&gear.to_string())
+                    ),
+                };
+            }
+            out.push('\n');
+        }
+        if format == Format::Html {
+            out.push_str("</pre>\n</body>\n</html>\n");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const SCHEM: &str = "\
+12*3..7
+....<..";
+
+    #[test]
+    fn ansi() {
+        let board: Board = SCHEM.parse().unwrap();
+        let drawn = board.render(&GearRule::default(), Format::Ansi);
+        let expected = [
+            "\x1b[1;32m12\x1b[0m\x1b[1;33m*\x1b[0m\x1b[1;32m3\x1b[0m",
+            "\x1b[2m..\x1b[0m\x1b[31m7\x1b[0m",
+            "   \x1b[33m* at 0, 2 with 12, 3: 36\x1b[0m\n",
+            "\x1b[2m....\x1b[0m\x1b[36m<\x1b[0m\x1b[2m..\x1b[0m\n",
+        ]
+        .concat();
+        assert_eq!(expected, drawn);
+    }
+
+    #[test]
+    fn html() {
+        let board: Board = SCHEM.parse().unwrap();
+        let drawn = board.render(&GearRule::default(), Format::Html);
+        assert!(drawn.starts_with("<!DOCTYPE html>"));
+        assert!(drawn.ends_with("</html>\n"));
+        assert!(drawn.contains(concat!(
+            "<span class=\"counted\">12</span><span class=\"gear\">*</span>",
+            "<span class=\"counted\">3</span><span class=\"blank\">..</span>",
+            "<span class=\"excluded\">7</span>",
+            "   <span class=\"ratio\">* at 0, 2 with 12, 3: 36</span>\n"
+        )));
+        assert!(drawn.contains("<span class=\"symbol\">&lt;</span>"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Splitting a sequence into runs of consecutive items with the same key.
+
+/// A run of items at `start..end` that all have `key`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span<K> {
+    pub start: usize,
+    pub end: usize,
+    pub key: K,
+}
+
+impl<K> Span<K> {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Iterator over the runs of `source` as keyed by `key_fn`, made by
+/// [`RunsExt::runs_by`]. Runs are never empty and two next to each other
+/// never share a key.
+///
+/// Going from the back needs to know how many items there are, so it's only
+/// there for sources that are both double ended and exact size.
+pub struct Runs<I: Iterator, F, K> {
+    source: I,
+    key_fn: F,
+    /// How many items have been taken from the front.
+    front: usize,
+    /// The first item of the next run from the front, already taken.
+    head: Option<(usize, K)>,
+    /// The last item of the next run from the back, already taken.
+    tail: Option<(usize, K)>,
+}
+
+pub trait RunsExt: Iterator + Sized {
+    /// Groups consecutive items by the key `key_fn` gives them.
+    fn runs_by<K: Eq, F: FnMut(Self::Item) -> K>(self, key_fn: F) -> Runs<Self, F, K> {
+        Runs {
+            source: self,
+            key_fn,
+            front: 0,
+            head: None,
+            tail: None,
+        }
+    }
+}
+
+impl<I: Iterator> RunsExt for I {}
+
+impl<I: Iterator, F: FnMut(I::Item) -> K, K> Runs<I, F, K> {
+    fn pull_front(&mut self) -> Option<(usize, K)> {
+        let item = self.source.next()?;
+        self.front += 1;
+        Some((self.front - 1, (self.key_fn)(item)))
+    }
+}
+
+impl<I: Iterator, F: FnMut(I::Item) -> K, K: Eq> Iterator for Runs<I, F, K> {
+    type Item = Span<K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, key) = match self.head.take().or_else(|| self.pull_front()) {
+            Some(first) => first,
+            None => {
+                let (idx, key) = self.tail.take()?;
+                return Some(Span {
+                    start: idx,
+                    end: idx + 1,
+                    key,
+                });
+            }
+        };
+        let mut end = start + 1;
+        loop {
+            match self.pull_front() {
+                Some((idx, k)) if k == key => end = idx + 1,
+                Some(next) => {
+                    self.head = Some(next);
+                    break;
+                }
+                None => {
+                    // Everything left is in the tail, which may carry on
+                    // this run.
+                    if let Some((idx, _)) = self.tail.take_if(|(_, k)| *k == key) {
+                        end = idx + 1;
+                    }
+                    break;
+                }
+            }
+        }
+        Some(Span { start, end, key })
+    }
+}
+
+impl<I, F, K> DoubleEndedIterator for Runs<I, F, K>
+where
+    I: DoubleEndedIterator + ExactSizeIterator,
+    F: FnMut(I::Item) -> K,
+    K: Eq,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pull_back = |runs: &mut Self| {
+            let item = runs.source.next_back()?;
+            Some((runs.front + runs.source.len(), (runs.key_fn)(item)))
+        };
+        let (last, key) = match self.tail.take().or_else(|| pull_back(self)) {
+            Some(last) => last,
+            None => {
+                let (idx, key) = self.head.take()?;
+                return Some(Span {
+                    start: idx,
+                    end: idx + 1,
+                    key,
+                });
+            }
+        };
+        let mut start = last;
+        loop {
+            match pull_back(self) {
+                Some((idx, k)) if k == key => start = idx,
+                Some(prev) => {
+                    self.tail = Some(prev);
+                    break;
+                }
+                None => {
+                    if let Some((idx, _)) = self.head.take_if(|(_, k)| *k == key) {
+                        start = idx;
+                    }
+                    break;
+                }
+            }
+        }
+        Some(Span {
+            start,
+            end: last + 1,
+            key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn span<K>(start: usize, end: usize, key: K) -> Span<K> {
+        Span { start, end, key }
+    }
+
+    #[test]
+    fn forwards() {
+        let t = [0, 0, 1, 0, 0, 0, 0, 1, 1, 1];
+        let spans: Vec<_> = t.iter().runs_by(|x| *x == 1).collect();
+        assert_eq!(
+            vec![
+                span(0, 2, false),
+                span(2, 3, true),
+                span(3, 7, false),
+                span(7, 10, true)
+            ],
+            spans
+        );
+
+        let words: Vec<_> = "aab1c"
+            .chars()
+            .runs_by(|c| c.is_alphabetic())
+            .map(|s| (s.len(), s.key))
+            .collect();
+        assert_eq!(vec![(3, true), (1, false), (1, true)], words);
+
+        assert_eq!(None, [0u8; 0].iter().runs_by(|x| *x).next());
+        let by_tens: Vec<_> = [3, 7, 12, 15, 31].into_iter().runs_by(|x| x / 10).collect();
+        assert_eq!(vec![span(0, 2, 0), span(2, 4, 1), span(4, 5, 3)], by_tens);
+    }
+
+    #[test]
+    fn backwards() {
+        let t = [0, 0, 1, 0, 0, 0, 0, 1, 1, 1];
+        let forwards: Vec<_> = t.iter().runs_by(|x| *x == 1).collect();
+        let mut backwards: Vec<_> = t.iter().runs_by(|x| *x == 1).rev().collect();
+        backwards.reverse();
+        assert_eq!(forwards, backwards);
+
+        // Every way of interleaving the two ends gives the same runs, including
+        // meeting in the middle of one.
+        let t = [5, 5, 6, 6, 6, 5, 7, 7];
+        let expected: Vec<_> = t.iter().runs_by(|x| *x).collect();
+        for pattern in 0u32..1 << 6 {
+            let mut runs = t.iter().runs_by(|x| *x);
+            let (mut front, mut back) = (vec![], vec![]);
+            for step in 0.. {
+                let span = if pattern >> (step % 6) & 1 == 1 {
+                    runs.next().inspect(|s| front.push(s.clone()))
+                } else {
+                    runs.next_back().inspect(|s| back.push(s.clone()))
+                };
+                if span.is_none() {
+                    break;
+                }
+            }
+            back.reverse();
+            front.extend(back);
+            assert_eq!(expected, front, "pattern {pattern:b}");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Times counting scratchcard matches with each way of storing the numbers,
+//! over generated cards.
+//!
+//!     cargo run --release -p day-4 --example card_bench -- [<millions of cards>]
+
+use std::{collections::HashSet, fmt::Write, time::Instant};
+
+use anyhow::Error;
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+
+use day_4::{part1, Card, Cards, Numbers};
+
+/// `count` different numbers from 1 to 99, like the puzzle's cards.
+fn numbers(rng: &mut StdRng, count: usize) -> Vec<usize> {
+    index::sample(rng, 99, count)
+        .into_iter()
+        .map(|n| n + 1)
+        .collect()
+}
+
+fn report<T>(name: &str, cards: usize, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let res = f();
+    let elapsed = start.elapsed();
+    let rate = cards as f64 / 1e6 / elapsed.as_secs_f64();
+    println!("{name:>12}: {elapsed:?} ({rate:.1}M cards/s)");
+    res
+}
+
+fn main() -> Result<(), Error> {
+    let millions: f64 = std::env::args()
+        .nth(1)
+        .map(|s| s.parse())
+        .transpose()?
+        .unwrap_or(2.0);
+    let count = (millions * 1e6) as usize;
+
+    println!("Generating {count} cards");
+    // Seeded, so the cards are the same on every run.
+    let mut rng = StdRng::seed_from_u64(0x2023_1204);
+    let cards: Vec<_> = (0..count)
+        .map(|_| (numbers(&mut rng, 10), numbers(&mut rng, 25)))
+        .collect();
+
+    // Each way stores the winning numbers, and the numbers we have are
+    // looked up in them one at a time, as `Card` does.
+    let hashed: Vec<HashSet<usize>> = cards
+        .iter()
+        .map(|(w, _)| w.iter().copied().collect())
+        .collect();
+    let sorted: Vec<_> = cards
+        .iter()
+        .map(|(w, _)| Numbers::sorted(w.clone()))
+        .collect();
+    let bits: Vec<_> = cards
+        .iter()
+        .map(|(w, _)| Numbers::bits(w).unwrap())
+        .collect();
+
+    let expected = report("hash set", count, || {
+        hashed
+            .iter()
+            .zip(&cards)
+            .map(|(w, (_, h))| h.iter().filter(|n| w.contains(n)).count())
+            .sum::<usize>()
+    });
+    let matches = |winners: &[Numbers]| {
+        winners
+            .iter()
+            .zip(&cards)
+            .map(|(w, (_, h))| h.iter().filter(|n| w.contains(**n)).count())
+            .sum::<usize>()
+    };
+    let by_sorted = report("sorted", count, || matches(&sorted));
+    let by_bits = report("bitset", count, || matches(&bits));
+    assert_eq!(expected, by_sorted, "sorted vectors disagree");
+    assert_eq!(expected, by_bits, "bitsets disagree");
+
+    let mut input = String::new();
+    for (i, (w, h)) in cards.iter().enumerate() {
+        let nums = |nums: &[usize]| nums.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>();
+        writeln!(
+            input,
+            "Card {}: {} | {}",
+            i + 1,
+            nums(w).join(" "),
+            nums(h).join(" ")
+        )?;
+    }
+    let parsed: Cards = report("parse", count, || input.parse())?;
+    let matches: usize = parsed.cards().iter().map(Card::matches).sum();
+    assert_eq!(expected, matches, "parsed cards disagree");
+    assert!(
+        parsed.cards().iter().all(
+            |c| matches!(c.winners(), Numbers::Bits(_)) && matches!(c.have(), Numbers::Bits(_))
+        ),
+        "generated cards should all fit in bitsets"
+    );
+    report("part 1", count, || part1(&parsed))?;
+    Ok(())
+}
@@ -0,0 +1,250 @@
+#![feature(ascii_char)]
+
+use anyhow::Error;
+use std::{num::ParseIntError, str::FromStr};
+use thiserror::Error;
+
+mod numbers;
+
+pub use numbers::Numbers;
+
+pub type ParsedInput = Cards;
+
+pub fn part1(input: &ParsedInput) -> Result<usize, Error> {
+    Ok(input.cards.iter().fold(0, |acc, c| acc + c.worth()))
+}
+
+pub fn part2(input: &ParsedInput) -> Result<usize, Error> {
+    let cards = &input.cards;
+    check_order(cards)?;
+
+    // won[i] is how many more copies of card i were won than of the card
+    // before it, so adding them up as we go gives the copies won of each
+    // card. A card wins its copies of a whole range of the cards after it
+    // with two writes, however long the range is.
+    let mut won: Vec<isize> = vec![0; cards.len() + 1];
+    let (mut running, mut total) = (0, 0);
+    for (i, card) in cards.iter().enumerate() {
+        running += won[i];
+        let copies = 1 + running;
+        total += copies;
+        let end = cards.len().min(i + 1 + card.matches());
+        won[i + 1] += copies;
+        won[end] -= copies;
+    }
+    Ok(total as usize)
+}
+
+/// Checks the cards are numbered 1, 2, 3 and so on. A number that isn't on
+/// any card is missing; if they're all there, the first card in the wrong
+/// place is out of order.
+fn check_order(cards: &[Card]) -> Result<(), CardError> {
+    let mut seen = vec![false; cards.len() + 1];
+    for card in cards.iter().filter(|c| c.number <= cards.len()) {
+        seen[card.number] = true;
+    }
+    if let Some(missing) = (1..=cards.len()).find(|n| !seen[*n]) {
+        return Err(CardError::Missing(missing));
+    }
+    match cards.iter().zip(1..).find(|(c, n)| c.number != *n) {
+        Some((card, expected)) => Err(CardError::OutOfOrder {
+            found: card.number,
+            expected,
+        }),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CardError {
+    #[error("Card {0} is missing")]
+    Missing(usize),
+    #[error("Card {found} is out of order, expected card {expected}")]
+    OutOfOrder { found: usize, expected: usize },
+}
+
+/// A scratchcard, keeping how many of its numbers won.
+#[derive(Debug)]
+pub struct Card {
+    number: usize,
+    winners: Numbers,
+    /// The numbers we have, each kept once however often it's listed.
+    have: Numbers,
+    /// How many of the numbers we have are winners, worked out once. A
+    /// winner we have twice counts twice.
+    matches: usize,
+}
+
+impl Card {
+    pub fn winners(&self) -> &Numbers {
+        &self.winners
+    }
+
+    pub fn have(&self) -> &Numbers {
+        &self.have
+    }
+
+    pub fn worth(&self) -> usize {
+        if self.matches == 0 {
+            0
+        } else {
+            2usize.pow(self.matches.saturating_sub(1) as u32)
+        }
+    }
+
+    pub fn matches(&self) -> usize {
+        self.matches
+    }
+}
+
+impl FromStr for Card {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((card_num, numbers)) = s.split_once(':') else {
+            return Err(anyhow::format_err!("Couldn't find card number"));
+        };
+        let number = card_num
+            .split_whitespace()
+            .skip(1)
+            .next()
+            .ok_or(anyhow::format_err!("Couldn't find card number"))?
+            .parse()?;
+        let Some((winners, have)) = numbers.split_once('|') else {
+            return Err(anyhow::format_err!("Couldn't find winners delimiter"));
+        };
+        let winners: Result<Vec<usize>, ParseIntError> = winners
+            .split_whitespace()
+            .map(|s| s.parse::<usize>())
+            .collect();
+        let have: Result<Vec<usize>, ParseIntError> = have
+            .split_whitespace()
+            .map(|s| s.parse::<usize>())
+            .collect();
+        let (winners, have) = (Numbers::new(winners?), have?);
+        let matches = have.iter().filter(|n| winners.contains(**n)).count();
+        Ok(Self {
+            number,
+            winners,
+            have: Numbers::new(have),
+            matches,
+        })
+    }
+}
+
+/// Every scratchcard in the pile, in the order they're listed.
+#[derive(Debug)]
+pub struct Cards {
+    cards: Vec<Card>,
+}
+
+impl Cards {
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+impl FromStr for Cards {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = s.lines().map(str::parse).collect::<Result<_, Error>>()?;
+        Ok(Self { cards })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_parse() {
+        let c: Result<Card, Error> = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".parse();
+
+        let c = c.expect("card shouuld parse ok");
+        assert_eq!(5, c.winners.len());
+        assert_eq!(8, c.have.len());
+        assert_eq!(4, c.matches());
+        assert_eq!(8, c.worth());
+
+        let c: Card = "Card 1: 41 48 | 48 41 48".parse().unwrap();
+        assert_eq!(2, c.have.len());
+        assert_eq!(3, c.matches());
+
+        let c: Result<Card, Error> = "Card 1 41 48 83 86 17 | 83 86  6 31 17  9 48 53".parse();
+        assert!(c.is_err());
+
+        let c: Result<Card, Error> = "Card 1: 41 48 83 86 17 83 86  6 31 17  9 48 53".parse();
+        assert!(c.is_err());
+    }
+
+    const EX_INPUT: &str = r#"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
+
+    #[test]
+    fn test_part1() {
+        let res = part1(&EX_INPUT.parse().unwrap());
+        assert_eq!(13, res.unwrap());
+    }
+
+    #[test]
+    fn ex_part2() {
+        let res = part2(&EX_INPUT.parse().unwrap());
+        assert_eq!(30, res.unwrap());
+    }
+
+    #[test]
+    fn part2_errors() {
+        let part2 = |input: &str| part2(&input.parse().unwrap());
+        assert_eq!(0, part2("").unwrap());
+
+        let missing = "Card 1: 1 | 1\nCard 3: 1 | 2";
+        let err = part2(missing).unwrap_err();
+        assert_eq!(Some(&CardError::Missing(2)), err.downcast_ref());
+
+        let swapped = "Card 2: 1 | 1\nCard 1: 1 | 2";
+        let err = part2(swapped).unwrap_err();
+        assert_eq!(
+            Some(&CardError::OutOfOrder {
+                found: 2,
+                expected: 1
+            }),
+            err.downcast_ref()
+        );
+
+        let shuffled = "Card 1: 1 | 1\nCard 3: 1 | 2\nCard 2: 1 | 2";
+        let err = part2(shuffled).unwrap_err();
+        assert_eq!(
+            Some(&CardError::OutOfOrder {
+                found: 3,
+                expected: 2
+            }),
+            err.downcast_ref()
+        );
+
+        // With card 1 twice there's no room left for card 2.
+        let repeated = "Card 1: 1 | 1\nCard 1: 1 | 2";
+        let err = part2(repeated).unwrap_err();
+        assert_eq!(Some(&CardError::Missing(2)), err.downcast_ref());
+    }
+
+    #[test]
+    fn big_numbers() {
+        let c: Card = "Card 1: 41 480 83 | 83 480 6 41".parse().unwrap();
+        assert!(matches!(c.winners, Numbers::Sorted(_)));
+        assert!(matches!(c.have, Numbers::Sorted(_)));
+        assert_eq!(3, c.matches());
+        assert_eq!(4, c.worth());
+    }
+
+    #[test]
+    fn part2_past_the_end() {
+        // Wins running past the last card are dropped.
+        let input = "Card 1: 1 2 3 | 1 2 3\nCard 2: 4 | 4";
+        assert_eq!(1 + 2, part2(&input.parse().unwrap()).unwrap());
+    }
+}
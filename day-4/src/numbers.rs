@@ -0,0 +1,83 @@
+//! Sets of scratchcard numbers that are quick to look numbers up in.
+
+/// The numbers below this fit in a bitset.
+const BITS: usize = 128;
+
+/// A set of numbers: a bitset when they're all below 128, which real cards
+/// always are, or a sorted vector when they aren't.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Numbers {
+    Bits([u64; BITS / 64]),
+    Sorted(Vec<usize>),
+}
+
+impl Numbers {
+    /// Stores `nums` as a bitset if it can, dropping repeats.
+    pub fn new(nums: Vec<usize>) -> Self {
+        Self::bits(&nums).unwrap_or_else(|| Self::sorted(nums))
+    }
+
+    /// A bitset of `nums`, unless one of them is too big for it.
+    pub fn bits(nums: &[usize]) -> Option<Self> {
+        let mut bits = [0; BITS / 64];
+        for num in nums {
+            if *num >= BITS {
+                return None;
+            }
+            bits[num / 64] |= 1 << (num % 64);
+        }
+        Some(Self::Bits(bits))
+    }
+
+    pub fn sorted(mut nums: Vec<usize>) -> Self {
+        nums.sort_unstable();
+        nums.dedup();
+        Self::Sorted(nums)
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Bits(bits) => bits.iter().map(|b| b.count_ones() as usize).sum(),
+            Self::Sorted(nums) => nums.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, num: usize) -> bool {
+        match self {
+            Self::Bits(bits) => num < BITS && bits[num / 64] & (1 << (num % 64)) != 0,
+            Self::Sorted(nums) => nums.binary_search(&num).is_ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sets() {
+        let small = Numbers::new(vec![41, 48, 83, 86, 17, 17]);
+        assert!(matches!(small, Numbers::Bits(_)));
+        assert_eq!(5, small.len());
+        assert!(small.contains(86) && !small.contains(85) && !small.contains(500));
+
+        let big = Numbers::new(vec![300, 83, 17, 9, 83]);
+        assert_eq!(Numbers::Sorted(vec![9, 17, 83, 300]), big);
+        assert!(big.contains(300) && !big.contains(41));
+
+        let have = [83, 86, 6, 31, 17, 9, 48, 53, 127];
+        let as_bits = Numbers::bits(&have).unwrap();
+        let as_sorted = Numbers::sorted(have.to_vec());
+        assert!(have
+            .iter()
+            .all(|n| as_bits.contains(*n) && as_sorted.contains(*n)));
+        assert!(!as_bits.contains(128) && !as_sorted.contains(128));
+        assert!(Numbers::new(vec![]).is_empty());
+    }
+}
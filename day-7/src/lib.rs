@@ -0,0 +1,320 @@
+use std::{cmp::Ordering, collections::HashMap, num::ParseIntError, str::FromStr};
+
+use anyhow::{format_err, Error};
+
+pub fn repl(input: &ParsedInput) -> Result<(), Error> {
+    let banner = format!(
+        "Loaded {} hands, commands: hand <cards>, bid <cards>, quit",
+        input.hands.len()
+    );
+    prompt::run(&banner, |line| command(input, line))
+}
+
+/// What one line typed at the repl prints.
+pub fn command(input: &ParsedInput, line: &str) -> String {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    match (command, words.next()) {
+        ("hand" | "bid", None) => format!("{command} needs five cards"),
+        ("hand" | "bid", Some(cards))
+            if cards.len() != 5 || !cards.chars().all(|c| "AKQJT98765432".contains(c)) =>
+        {
+            format!("Not a hand: {cards}")
+        }
+        ("hand", Some(cards)) => match format!("{cards} 0").parse::<BiddedHand>() {
+            Ok(mut hand) => {
+                let kind = hand.kind;
+                hand.jokerize();
+                format!("{cards} is {kind:?}, {:?} with jokers", hand.kind)
+            }
+            Err(e) => format!("Not a hand: {e}"),
+        },
+        ("bid", Some(cards)) => {
+            let hand: Vec<Card> = cards.chars().map(|c| Card::try_from(c).unwrap()).collect();
+            match input.hands.iter().find(|h| h.cards == hand) {
+                Some(h) => format!("{cards} bids {}", h.bid),
+                None => format!("{cards} is not in the input"),
+            }
+        }
+        _ => format!("Unknown command: {command}"),
+    }
+}
+
+#[allow(unused)]
+fn numbers_ws_delimited(s: &str) -> Result<Vec<usize>, ParseIntError> {
+    s.split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<usize>, ParseIntError>>()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
+enum Card {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Card {
+    fn joker_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if self == &Self::Jack && other != &Self::Jack {
+            return Ordering::Less;
+        }
+        if other == &Self::Jack && self != &Self::Jack {
+            return Ordering::Greater;
+        }
+        self.cmp(other)
+    }
+}
+
+impl TryFrom<char> for Card {
+    type Error = Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
+            'A' => Self::Ace,
+            'K' => Self::King,
+            'Q' => Self::Queen,
+            'J' => Self::Jack,
+            'T' => Self::Ten,
+            '9' => Self::Nine,
+            '8' => Self::Eight,
+            '7' => Self::Seven,
+            '6' => Self::Six,
+            '5' => Self::Five,
+            '4' => Self::Four,
+            '3' => Self::Three,
+            '2' => Self::Two,
+            _ => return Err(format_err!("Not a card: {value}")),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Ord, Eq)]
+enum HandKind {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeKind,
+    FullHouse,
+    FourKind,
+    FiveKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BiddedHand {
+    cards: Vec<Card>,
+    kind: HandKind,
+    bid: usize,
+}
+
+impl BiddedHand {
+    fn jokerize(&mut self) {
+        let mut cards = self.cards.clone();
+        cards.as_mut_slice().sort();
+        let mut counts = cards.iter().fold(HashMap::new(), |mut m, card| {
+            *m.entry(*card).or_insert(0usize) += 1;
+            m
+        });
+        let Some(jokers) = counts.remove(&Card::Jack) else {
+            return;
+        };
+        if jokers == 5 {
+            return;
+        }
+        let top_key = *counts.iter().max_by_key(|(_k, v)| **v).unwrap().0;
+        *counts.get_mut(&top_key).unwrap() += jokers;
+        let mut values: Vec<usize> = counts.values().copied().collect();
+        values.sort();
+        values.reverse();
+        self.kind = match values[0] {
+            5 => HandKind::FiveKind,
+            4 => HandKind::FourKind,
+            3 => match values[1] {
+                2 => HandKind::FullHouse,
+                _ => HandKind::ThreeKind,
+            },
+            2 => match values[1] {
+                2 => HandKind::TwoPair,
+                _ => HandKind::OnePair,
+            },
+            1 => HandKind::HighCard,
+            _ => panic!("Not possible"),
+        }
+    }
+
+    fn joker_ord(&self, other: &Self) -> std::cmp::Ordering {
+        match self.kind.cmp(&other.kind) {
+            Ordering::Equal => {
+                for (mine, theirs) in self.cards.iter().zip(other.cards.iter()) {
+                    match mine.joker_cmp(theirs) {
+                        Ordering::Equal => continue,
+                        v => return v,
+                    }
+                }
+                Ordering::Equal
+            }
+            v => return v,
+        }
+    }
+}
+
+impl From<&Vec<Card>> for HandKind {
+    fn from(value: &Vec<Card>) -> Self {
+        assert_eq!(value.len(), 5);
+        let mut cards = value.clone();
+        cards.as_mut_slice().sort();
+        let counts = cards.iter().fold(HashMap::new(), |mut m, card| {
+            *m.entry(card).or_insert(0usize) += 1;
+            m
+        });
+        let mut values: Vec<usize> = counts.values().copied().collect();
+        values.sort();
+        values.reverse();
+        match values[0] {
+            5 => Self::FiveKind,
+            4 => Self::FourKind,
+            3 => match values[1] {
+                2 => Self::FullHouse,
+                _ => Self::ThreeKind,
+            },
+            2 => match values[1] {
+                2 => Self::TwoPair,
+                _ => Self::OnePair,
+            },
+            1 => Self::HighCard,
+            _ => panic!("Not possible"),
+        }
+    }
+}
+
+impl PartialOrd for BiddedHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BiddedHand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.kind.cmp(&other.kind) {
+            Ordering::Equal => self.cards.cmp(&other.cards),
+            x => x,
+        }
+    }
+}
+
+impl FromStr for BiddedHand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hand, bid) = s
+            .split_once(' ')
+            .ok_or_else(|| format_err!("Expected a hand and a bid: {s}"))?;
+        let bid = bid.parse()?;
+
+        let cards = hand
+            .chars()
+            .map(Card::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        if cards.len() != 5 {
+            return Err(format_err!("Expected five cards: {hand}"));
+        }
+        let kind = HandKind::from(&cards);
+        Ok(Self { cards, kind, bid })
+    }
+}
+
+pub struct Hands {
+    hands: Vec<BiddedHand>,
+}
+
+impl FromStr for Hands {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hands = s
+            .lines()
+            .map(str::parse)
+            .collect::<Result<Vec<BiddedHand>, Error>>()?;
+        Ok(Hands { hands })
+    }
+}
+
+pub type ParsedInput = Hands;
+
+pub fn part1(input: &ParsedInput) -> Result<usize, Error> {
+    let mut ranked = input.hands.clone();
+    ranked.sort();
+    let mut winnings = 0;
+    for (i, hand) in ranked.iter().enumerate() {
+        winnings += (i + 1) * hand.bid
+    }
+    Ok(winnings)
+}
+
+pub fn part2(input: &ParsedInput) -> Result<usize, Error> {
+    let mut ranked = input.hands.clone();
+    for hand in ranked.iter_mut() {
+        hand.jokerize();
+    }
+    ranked.sort_by(BiddedHand::joker_ord);
+    let mut winnings = 0;
+    for (i, hand) in ranked.iter().enumerate() {
+        winnings += (i + 1) * hand.bid
+    }
+    Ok(winnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EX_INPUT: &str = r#"32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483"#;
+
+    #[test]
+    fn parse_input() {
+        let parsed: ParsedInput = EX_INPUT.parse().expect("parses ok");
+        assert_eq!(5, parsed.hands.len());
+
+        assert!("ZZZZZ 1".parse::<ParsedInput>().is_err());
+        assert!("32T3 765".parse::<ParsedInput>().is_err());
+        assert!("32T3K".parse::<ParsedInput>().is_err());
+    }
+
+    #[test]
+    fn ex_part1() {
+        assert_eq!(6440, part1(&(EX_INPUT.parse().unwrap())).unwrap());
+    }
+
+    #[test]
+    fn ex_part2() {
+        assert_eq!(5905, part2(&(EX_INPUT.parse().unwrap())).unwrap());
+    }
+
+    #[test]
+    fn repl_commands() {
+        let parsed: ParsedInput = EX_INPUT.parse().unwrap();
+        assert_eq!(
+            "KTJJT is TwoPair, FourKind with jokers",
+            command(&parsed, "hand KTJJT")
+        );
+        assert_eq!("QQQJA bids 483", command(&parsed, "bid QQQJA"));
+        assert_eq!("AAAAA is not in the input", command(&parsed, "bid AAAAA"));
+        assert_eq!("Not a hand: KTJJ", command(&parsed, "hand KTJJ"));
+        assert_eq!("bid needs five cards", command(&parsed, "bid"));
+        assert_eq!("Unknown command: deal", command(&parsed, "deal"));
+    }
+}
@@ -0,0 +1,58 @@
+//! The prompt loop behind each day's `repl` mode. Days only say what a line
+//! does; reading lines, quitting and skipping blank lines happen here.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Error;
+
+/// Prints `banner`, then hands each line typed on stdin to `command` and
+/// prints what it returns, until `quit`, `exit` or the end of input.
+pub fn run(banner: &str, command: impl FnMut(&str) -> String) -> Result<(), Error> {
+    run_on(std::io::stdin().lock(), std::io::stdout(), banner, command)
+}
+
+/// [`run`] over any input and output.
+pub fn run_on(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    banner: &str,
+    mut command: impl FnMut(&str) -> String,
+) -> Result<(), Error> {
+    writeln!(output, "{banner}")?;
+    let mut line = String::new();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match line.trim() {
+            "" => continue,
+            "quit" | "exit" => return Ok(()),
+            line => writeln!(output, "{}", command(line))?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prompts() {
+        let mut out = Vec::new();
+        let input = "hello\n\n  twice \nquit\nnever\n".as_bytes();
+        run_on(input, &mut out, "Hi", |line| line.repeat(2)).unwrap();
+        assert_eq!(
+            "Hi\n> hellohello\n> > twicetwice\n> ",
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = Vec::new();
+        run_on("a".as_bytes(), &mut out, "Hi", str::to_uppercase).unwrap();
+        assert_eq!("Hi\n> A\n> ", String::from_utf8(out).unwrap());
+    }
+}